@@ -2,3 +2,4 @@ export * from './validation.utils';
 export * from './crypto.utils';
 export * from './transform.utils';
 export * from './string.utils';
+export * from './merkle.utils';
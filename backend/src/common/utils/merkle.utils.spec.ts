@@ -0,0 +1,80 @@
+import { readFileSync } from 'fs';
+import { join } from 'path';
+import { MerkleUtils } from './merkle.utils';
+
+interface VectorProof {
+  index: number;
+  leaf: string;
+  siblings: string[];
+  directions: number;
+}
+
+interface VectorTree {
+  leaves: string[];
+  root: string;
+  proofs: VectorProof[];
+}
+
+// Shared with the contract tests in stellar-contracts/src/test.rs
+const vectors: { trees: VectorTree[] } = JSON.parse(
+  readFileSync(
+    join(
+      __dirname,
+      '../../../../stellar-contracts/test_vectors/merkle_batch.json',
+    ),
+    'utf8',
+  ),
+);
+
+const hex = (value: string) => Buffer.from(value, 'hex');
+
+describe('MerkleUtils', () => {
+  describe.each(vectors.trees)('tree with $leaves.length leaves', (tree) => {
+    const leaves = tree.leaves.map(hex);
+    const built = MerkleUtils.buildTree(leaves);
+
+    it('should reproduce the shared root', () => {
+      expect(built.root.toString('hex')).toBe(tree.root);
+    });
+
+    it('should reproduce the shared proofs', () => {
+      tree.proofs.forEach((expected) => {
+        const proof = MerkleUtils.getProof(
+          built,
+          leaves[expected.index],
+          expected.index,
+        );
+        expect(proof.siblings.map((s) => s.toString('hex'))).toEqual(
+          expected.siblings,
+        );
+        expect(proof.directions).toBe(expected.directions);
+        expect(MerkleUtils.verifyProof(built.root, proof)).toBe(true);
+      });
+    });
+  });
+
+  it('should reject a proof with a flipped direction bit', () => {
+    const tree = vectors.trees[2];
+    const built = MerkleUtils.buildTree(tree.leaves.map(hex));
+    const proof = MerkleUtils.getProof(built, hex(tree.leaves[1]), 1);
+
+    expect(
+      MerkleUtils.verifyProof(built.root, {
+        ...proof,
+        directions: proof.directions ^ 1,
+      }),
+    ).toBe(false);
+  });
+
+  it('should reject an internal node presented as a leaf', () => {
+    const built = MerkleUtils.buildTree(vectors.trees[1].leaves.map(hex));
+
+    expect(
+      MerkleUtils.verifyProof(built.root, {
+        leaf: built.root,
+        siblings: [],
+        directions: 0,
+      }),
+    ).toBe(false);
+  });
+});
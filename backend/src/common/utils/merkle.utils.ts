@@ -0,0 +1,135 @@
+import { createHash } from 'crypto';
+
+/** Domain-separation prefix for leaf hashes */
+export const MERKLE_LEAF_PREFIX = 0x00;
+/** Domain-separation prefix for internal node hashes */
+export const MERKLE_NODE_PREFIX = 0x01;
+
+/**
+ * Inclusion proof in the format accepted by the contract's `verify_merkle_batch`
+ */
+export interface MerkleProof {
+  /** 32-byte leaf value */
+  leaf: Buffer;
+  /** Sibling hashes from the leaf level upwards */
+  siblings: Buffer[];
+  /** Bit i is set when siblings[i] is the left-hand node */
+  directions: number;
+}
+
+export interface MerkleTree {
+  /** Hashed levels, from leaf hashes (index 0) up to the root */
+  levels: Buffer[][];
+  root: Buffer;
+}
+
+/**
+ * Batch Merkle tree builder compatible with the certificate contract.
+ *
+ * - leaf hash = sha256(0x00 || leaf)
+ * - node hash = sha256(0x01 || left || right)
+ * - an odd node at the end of a level is promoted unchanged
+ *
+ * See stellar-contracts/MERKLE_README.md for the full construction.
+ */
+export class MerkleUtils {
+  /**
+   * Hashes a 32-byte leaf value
+   */
+  static hashLeaf(leaf: Buffer): Buffer {
+    return createHash('sha256')
+      .update(Buffer.from([MERKLE_LEAF_PREFIX]))
+      .update(leaf)
+      .digest();
+  }
+
+  /**
+   * Hashes two child nodes into their parent
+   */
+  static hashNode(left: Buffer, right: Buffer): Buffer {
+    return createHash('sha256')
+      .update(Buffer.from([MERKLE_NODE_PREFIX]))
+      .update(left)
+      .update(right)
+      .digest();
+  }
+
+  /**
+   * Builds a tree over 32-byte leaf values
+   * @param leaves - Leaf values in batch order
+   */
+  static buildTree(leaves: Buffer[]): MerkleTree {
+    if (leaves.length === 0) {
+      throw new Error('Cannot build a Merkle tree without leaves');
+    }
+    leaves.forEach((leaf) => {
+      if (leaf.length !== 32) {
+        throw new Error('Merkle leaves must be 32 bytes');
+      }
+    });
+
+    const levels: Buffer[][] = [leaves.map((leaf) => this.hashLeaf(leaf))];
+    while (levels[levels.length - 1].length > 1) {
+      const current = levels[levels.length - 1];
+      const next: Buffer[] = [];
+      for (let i = 0; i < current.length; i += 2) {
+        next.push(
+          i + 1 < current.length
+            ? this.hashNode(current[i], current[i + 1])
+            : current[i],
+        );
+      }
+      levels.push(next);
+    }
+
+    return { levels, root: levels[levels.length - 1][0] };
+  }
+
+  /**
+   * Builds the inclusion proof for the leaf at `index`
+   */
+  static getProof(tree: MerkleTree, leaf: Buffer, index: number): MerkleProof {
+    if (index < 0 || index >= tree.levels[0].length) {
+      throw new Error('Leaf index out of range');
+    }
+
+    const siblings: Buffer[] = [];
+    let directions = 0;
+    let position = index;
+
+    for (const level of tree.levels.slice(0, -1)) {
+      if (position % 2 === 1) {
+        directions |= 1 << siblings.length;
+        siblings.push(level[position - 1]);
+      } else if (position + 1 < level.length) {
+        siblings.push(level[position + 1]);
+      }
+      position = Math.floor(position / 2);
+    }
+
+    return { leaf, siblings, directions: directions >>> 0 };
+  }
+
+  /**
+   * Verifies a proof against a root, mirroring the on-chain check
+   */
+  static verifyProof(root: Buffer, proof: MerkleProof): boolean {
+    const depth = proof.siblings.length;
+    if (depth > 32) {
+      return false;
+    }
+    if (depth < 32 && proof.directions >>> depth !== 0) {
+      return false;
+    }
+
+    let hash = this.hashLeaf(proof.leaf);
+    proof.siblings.forEach((sibling, i) => {
+      hash =
+        (proof.directions >>> i) & 1
+          ? this.hashNode(sibling, hash)
+          : this.hashNode(hash, sibling);
+    });
+
+    return hash.equals(root);
+  }
+}
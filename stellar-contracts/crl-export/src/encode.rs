@@ -0,0 +1,218 @@
+//! RFC 5280 encoding of the on-chain CRL.
+
+use core::str::FromStr;
+use core::time::Duration;
+
+use ed25519_dalek::pkcs8::DecodePrivateKey;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+use x509_cert::crl::{CertificateList, RevokedCert, TbsCertList};
+use x509_cert::der::asn1::{BitString, GeneralizedTime, OctetString, Uint, UtcTime};
+use x509_cert::der::oid::db::{rfc5280, rfc8410};
+use x509_cert::der::oid::ObjectIdentifier;
+use x509_cert::der::pem::LineEnding;
+use x509_cert::der::{DateTime, Decode, Encode};
+use x509_cert::ext::pkix::{AuthorityKeyIdentifier, CrlNumber, CrlReason};
+use x509_cert::ext::Extension;
+use x509_cert::name::Name;
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::spki::AlgorithmIdentifierOwned;
+use x509_cert::time::Time;
+use x509_cert::Version;
+
+use crate::model::{CertificateRevocationList, RevocationReason};
+use crate::ExportError;
+
+/// PEM label for X.509 CRLs (RFC 7468 section 6).
+pub const PEM_LABEL: &str = "X509 CRL";
+
+// Serial numbers are capped at 20 octets including the sign byte.
+const SERIAL_NUMBER_LENGTH: usize = 19;
+
+// RFC 7093 method 1: leftmost 160 bits of the SHA-256 of the public key.
+const KEY_IDENTIFIER_LENGTH: usize = 20;
+
+/// Options that are not part of the on-chain CRL.
+#[derive(Clone, Debug, Default)]
+pub struct ExportOptions {
+    /// Distinguished name of the CRL issuer. Defaults to `CN=<issuer address>`.
+    pub issuer_name: Option<String>,
+}
+
+/// Maps a contract revocation reason to its RFC 5280 `CRLReason`.
+///
+/// `Other` has no RFC 5280 counterpart and maps to `None`, in which case the
+/// reasonCode extension is omitted, as RFC 5280 recommends for `unspecified`.
+pub fn reason_code(reason: &RevocationReason) -> Option<CrlReason> {
+    match reason {
+        RevocationReason::KeyCompromise => Some(CrlReason::KeyCompromise),
+        RevocationReason::CACompromise => Some(CrlReason::CaCompromise),
+        RevocationReason::AffiliationChanged => Some(CrlReason::AffiliationChanged),
+        RevocationReason::Superseded => Some(CrlReason::Superseded),
+        RevocationReason::CessationOfOperation => Some(CrlReason::CessationOfOperation),
+        RevocationReason::CertificateHold => Some(CrlReason::CertificateHold),
+        RevocationReason::RemoveFromCRL => Some(CrlReason::RemoveFromCRL),
+        RevocationReason::PrivilegeWithdrawn => Some(CrlReason::PrivilegeWithdrawn),
+        RevocationReason::AACompromise => Some(CrlReason::AaCompromise),
+        RevocationReason::Other(_) => None,
+    }
+}
+
+/// Derives the X.509 serial number for an on-chain certificate id.
+///
+/// Certificate ids are free-form strings of up to 128 bytes, so they are
+/// hashed the same way the contract keys its status tree (SHA-256 of the id)
+/// and truncated to fit the 20 octet serial number limit.
+pub fn serial_number(certificate_id: &str) -> Result<SerialNumber, ExportError> {
+    let digest = Sha256::digest(certificate_id.as_bytes());
+    Ok(SerialNumber::new(&digest[..SERIAL_NUMBER_LENGTH])?)
+}
+
+/// Key identifier used for the AKI extension when the issuer has not set one.
+pub fn key_identifier(key: &VerifyingKey) -> Vec<u8> {
+    Sha256::digest(key.as_bytes())[..KEY_IDENTIFIER_LENGTH].to_vec()
+}
+
+/// Loads an ed25519 signing key from a PKCS#8 document, PEM or DER.
+pub fn load_signing_key(data: &[u8]) -> Result<SigningKey, ExportError> {
+    let key = match core::str::from_utf8(data) {
+        Ok(pem) if pem.trim_start().starts_with("-----BEGIN") => SigningKey::from_pkcs8_pem(pem),
+        _ => SigningKey::from_pkcs8_der(data),
+    };
+    key.map_err(|e| ExportError::InvalidKey(e.to_string()))
+}
+
+/// Encodes and signs `crl` as a DER `CertificateList`.
+pub fn encode_der(
+    crl: &CertificateRevocationList,
+    key: &SigningKey,
+    options: &ExportOptions,
+) -> Result<Vec<u8>, ExportError> {
+    let issuer_name = match &options.issuer_name {
+        Some(name) => name.clone(),
+        None => format!("CN={}", crl.issuer),
+    };
+    let issuer =
+        Name::from_str(&issuer_name).map_err(|_| ExportError::InvalidIssuerName(issuer_name))?;
+
+    let mut revoked = Vec::with_capacity(crl.revoked_certificates.len());
+    for entry in &crl.revoked_certificates {
+        let mut extensions = Vec::new();
+        if let Some(reason) = reason_code(&entry.reason) {
+            extensions.push(extension(rfc5280::ID_CE_CRL_REASONS, &reason)?);
+        }
+        if let Some(invalidity_date) = entry.invalidity_date {
+            // invalidityDate is always a GeneralizedTime (RFC 5280 section 5.3.2)
+            let time = GeneralizedTime::from_date_time(date_time(invalidity_date)?);
+            extensions.push(extension(rfc5280::ID_CE_INVALIDITY_DATE, &time)?);
+        }
+        revoked.push(RevokedCert {
+            serial_number: serial_number(&entry.certificate_id)?,
+            revocation_date: time(entry.revocation_date)?,
+            crl_entry_extensions: if extensions.is_empty() {
+                None
+            } else {
+                Some(extensions)
+            },
+        });
+    }
+
+    let key_id = match &crl.authority_key_identifier {
+        Some(aki) => aki.clone(),
+        None => key_identifier(&key.verifying_key()),
+    };
+    let aki = AuthorityKeyIdentifier {
+        key_identifier: Some(OctetString::new(key_id)?),
+        authority_cert_issuer: None,
+        authority_cert_serial_number: None,
+    };
+    let crl_number = CrlNumber(Uint::new(&crl.crl_number.to_be_bytes())?);
+
+    let tbs_cert_list = TbsCertList {
+        version: Version::V2,
+        signature: signature_algorithm(),
+        issuer,
+        this_update: time(crl.this_update)?,
+        next_update: Some(time(crl.next_update)?),
+        // An empty list MUST be omitted rather than encoded as an empty SEQUENCE
+        revoked_certificates: if revoked.is_empty() {
+            None
+        } else {
+            Some(revoked)
+        },
+        crl_extensions: Some(vec![
+            extension(rfc5280::ID_CE_AUTHORITY_KEY_IDENTIFIER, &aki)?,
+            extension(rfc5280::ID_CE_CRL_NUMBER, &crl_number)?,
+        ]),
+    };
+
+    let signature = key.sign(&tbs_cert_list.to_der()?);
+    let crl = CertificateList {
+        tbs_cert_list,
+        signature_algorithm: signature_algorithm(),
+        signature: BitString::from_bytes(&signature.to_bytes())?,
+    };
+    Ok(crl.to_der()?)
+}
+
+/// Encodes and signs `crl` as a PEM `X509 CRL` document.
+pub fn encode_pem(
+    crl: &CertificateRevocationList,
+    key: &SigningKey,
+    options: &ExportOptions,
+) -> Result<String, ExportError> {
+    let der = encode_der(crl, key, options)?;
+    Ok(
+        x509_cert::der::pem::encode_string(PEM_LABEL, LineEnding::LF, &der)
+            .map_err(x509_cert::der::Error::from)?,
+    )
+}
+
+/// Parses a DER CRL and checks its ed25519 signature against `key`.
+pub fn verify_der(der: &[u8], key: &VerifyingKey) -> Result<CertificateList, ExportError> {
+    let crl = CertificateList::from_der(der)?;
+    if crl.signature_algorithm.oid != rfc8410::ID_ED_25519
+        || crl.tbs_cert_list.signature.oid != rfc8410::ID_ED_25519
+    {
+        return Err(ExportError::UnsupportedAlgorithm);
+    }
+    let signature = crl
+        .signature
+        .as_bytes()
+        .and_then(|bytes| Signature::from_slice(bytes).ok())
+        .ok_or(ExportError::InvalidSignature)?;
+    key.verify(&crl.tbs_cert_list.to_der()?, &signature)
+        .map_err(|_| ExportError::InvalidSignature)?;
+    Ok(crl)
+}
+
+fn signature_algorithm() -> AlgorithmIdentifierOwned {
+    AlgorithmIdentifierOwned {
+        oid: rfc8410::ID_ED_25519,
+        parameters: None,
+    }
+}
+
+fn extension(oid: ObjectIdentifier, value: &impl Encode) -> Result<Extension, ExportError> {
+    // None of the extensions written here are critical
+    Ok(Extension {
+        extn_id: oid,
+        critical: false,
+        extn_value: OctetString::new(value.to_der()?)?,
+    })
+}
+
+fn date_time(timestamp: u64) -> Result<DateTime, ExportError> {
+    DateTime::from_unix_duration(Duration::from_secs(timestamp))
+        .map_err(|_| ExportError::InvalidTimestamp(timestamp))
+}
+
+// RFC 5280 section 5.1.2.4: UTCTime through 2049, GeneralizedTime from 2050
+fn time(timestamp: u64) -> Result<Time, ExportError> {
+    let date_time = date_time(timestamp)?;
+    if date_time.year() <= UtcTime::MAX_YEAR {
+        Ok(UtcTime::from_date_time(date_time)?.into())
+    } else {
+        Ok(GeneralizedTime::from_date_time(date_time).into())
+    }
+}
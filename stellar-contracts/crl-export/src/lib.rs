@@ -0,0 +1,58 @@
+//! Export of the on-chain certificate revocation list as an RFC 5280 X.509 CRL.
+//!
+//! Takes the `CertificateRevocationList` returned by `CRLContract::get_crl_info`
+//! (as JSON) and produces a v2 `CertificateList` signed with a locally held
+//! ed25519 key, so that existing PKI tooling can consume StellarCert
+//! revocations.
+
+pub mod encode;
+pub mod model;
+
+#[cfg(test)]
+mod test;
+
+use core::fmt;
+
+pub use encode::{
+    encode_der, encode_pem, key_identifier, load_signing_key, reason_code, serial_number,
+    verify_der, ExportOptions, PEM_LABEL,
+};
+pub use model::{CertificateRevocationList, DeltaCRL, RevocationReason, RevokedCertificate};
+
+/// Errors raised while exporting a CRL.
+#[derive(Debug)]
+pub enum ExportError {
+    /// ASN.1 encoding or decoding failed.
+    Der(x509_cert::der::Error),
+    /// The issuer distinguished name is not a valid RFC 4514 string.
+    InvalidIssuerName(String),
+    /// The signing key could not be loaded.
+    InvalidKey(String),
+    /// A timestamp cannot be represented as an X.509 time.
+    InvalidTimestamp(u64),
+    /// The CRL is not signed with ed25519.
+    UnsupportedAlgorithm,
+    /// The CRL signature does not verify.
+    InvalidSignature,
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Der(err) => write!(f, "DER error: {}", err),
+            ExportError::InvalidIssuerName(name) => write!(f, "Invalid issuer name: {}", name),
+            ExportError::InvalidKey(err) => write!(f, "Invalid signing key: {}", err),
+            ExportError::InvalidTimestamp(ts) => write!(f, "Timestamp out of range: {}", ts),
+            ExportError::UnsupportedAlgorithm => write!(f, "CRL is not signed with ed25519"),
+            ExportError::InvalidSignature => write!(f, "CRL signature does not verify"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<x509_cert::der::Error> for ExportError {
+    fn from(err: x509_cert::der::Error) -> Self {
+        ExportError::Der(err)
+    }
+}
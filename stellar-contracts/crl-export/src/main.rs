@@ -0,0 +1,75 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use crl_export::{
+    encode_der, encode_pem, load_signing_key, CertificateRevocationList, ExportOptions,
+};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    Pem,
+    Der,
+}
+
+/// Encode the output of `get_crl_info` as a signed RFC 5280 CRL.
+#[derive(Debug, Parser)]
+#[command(name = "crl-export", version)]
+struct Args {
+    /// JSON output of `get_crl_info`, or `-` for stdin
+    #[arg(short, long, default_value = "-")]
+    input: String,
+
+    /// ed25519 signing key as a PKCS#8 PEM or DER file
+    #[arg(short, long)]
+    key: PathBuf,
+
+    /// Issuer distinguished name (RFC 4514), defaults to `CN=<issuer address>`
+    #[arg(long)]
+    issuer_name: Option<String>,
+
+    /// Output encoding
+    #[arg(short, long, value_enum, default_value_t = Format::Pem)]
+    format: Format,
+
+    /// Output file, stdout if omitted
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    let json = if args.input == "-" {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        buf
+    } else {
+        fs::read_to_string(&args.input)?
+    };
+    let crl = CertificateRevocationList::from_json(&json)?;
+    let key = load_signing_key(&fs::read(&args.key)?)?;
+    let options = ExportOptions {
+        issuer_name: args.issuer_name,
+    };
+
+    let encoded = match args.format {
+        Format::Pem => encode_pem(&crl, &key, &options)?.into_bytes(),
+        Format::Der => encode_der(&crl, &key, &options)?,
+    };
+    match args.output {
+        Some(path) => fs::write(path, encoded)?,
+        None => io::stdout().write_all(&encoded)?,
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("crl-export: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
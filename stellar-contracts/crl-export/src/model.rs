@@ -0,0 +1,93 @@
+//! Off-chain mirror of the CRL contract types.
+//!
+//! The field names and enum representation follow the JSON that
+//! `stellar contract invoke -- get_crl_info` prints: addresses and strings are
+//! JSON strings, `u64` values are numbers, `Bytes` are hex strings and unit
+//! enum variants are bare strings (`"KeyCompromise"`), while `Other` is
+//! `{"Other": "..."}`.
+
+use serde::{Deserialize, Serialize};
+
+/// Revocation reason as stored by `CRLContract`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RevocationReason {
+    KeyCompromise,
+    CACompromise,
+    AffiliationChanged,
+    Superseded,
+    CessationOfOperation,
+    CertificateHold,
+    RemoveFromCRL,
+    PrivilegeWithdrawn,
+    AACompromise,
+    Other(String),
+}
+
+/// A single revoked certificate entry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevokedCertificate {
+    pub certificate_id: String,
+    pub issuer: String,
+    pub revocation_date: u64,
+    pub reason: RevocationReason,
+    pub invalidity_date: Option<u64>,
+}
+
+/// The full revocation list for one issuer, as returned by `get_crl_info`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateRevocationList {
+    pub issuer: String,
+    pub this_update: u64,
+    pub next_update: u64,
+    pub revoked_certificates: Vec<RevokedCertificate>,
+    #[serde(with = "hex_bytes")]
+    pub merkle_root: Option<Vec<u8>>,
+    pub crl_number: u64,
+    #[serde(with = "hex_bytes")]
+    pub authority_key_identifier: Option<Vec<u8>>,
+}
+
+/// Entries added and removed between two CRL numbers, as returned by
+/// `get_delta_crl`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeltaCRL {
+    pub issuer: String,
+    pub base_crl_number: u64,
+    pub crl_number: u64,
+    pub this_update: u64,
+    pub next_update: u64,
+    #[serde(with = "hex_bytes")]
+    pub merkle_root: Option<Vec<u8>>,
+    pub added: Vec<RevokedCertificate>,
+    pub removed: Vec<String>,
+}
+
+impl CertificateRevocationList {
+    /// Parses the JSON output of `get_crl_info`.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Serde adapter for optional `Bytes` values encoded as hex strings.
+mod hex_bytes {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(bytes) => serializer.serialize_some(&hex::encode(bytes)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| hex::decode(s).map_err(D::Error::custom))
+            .transpose()
+    }
+}
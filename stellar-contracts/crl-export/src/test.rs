@@ -0,0 +1,229 @@
+use ed25519_dalek::pkcs8::EncodePrivateKey;
+use ed25519_dalek::SigningKey;
+use x509_cert::der::pem::LineEnding;
+use x509_cert::der::{Decode, Encode};
+use x509_cert::ext::pkix::CrlReason;
+use x509_cert::time::Time;
+use x509_parser::extensions::ParsedExtension;
+use x509_parser::prelude::FromDer;
+use x509_parser::revocation_list::CertificateRevocationList as ParsedCrl;
+use x509_parser::x509::X509Version;
+
+use crate::*;
+
+const FIXTURE: &str = include_str!("../fixtures/crl_info.json");
+
+fn signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+fn fixture() -> CertificateRevocationList {
+    CertificateRevocationList::from_json(FIXTURE).unwrap()
+}
+
+#[test]
+fn test_fixture_round_trips_through_der_parser() {
+    let crl = fixture();
+    let der = encode_der(&crl, &signing_key(), &ExportOptions::default()).unwrap();
+
+    // Decode with an independent parser rather than the encoder's own types
+    let (rest, parsed) = ParsedCrl::from_der(&der).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(parsed.version(), Some(X509Version::V2));
+    assert_eq!(parsed.issuer().to_string(), format!("CN={}", crl.issuer));
+    assert_eq!(parsed.last_update().timestamp(), crl.this_update as i64);
+    assert_eq!(
+        parsed.next_update().unwrap().timestamp(),
+        crl.next_update as i64
+    );
+    assert_eq!(parsed.crl_number().unwrap().to_string(), "5");
+
+    let aki = parsed
+        .extensions()
+        .iter()
+        .find_map(|ext| match ext.parsed_extension() {
+            ParsedExtension::AuthorityKeyIdentifier(aki) => Some(aki),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        aki.key_identifier.as_ref().unwrap().0,
+        crl.authority_key_identifier.as_deref().unwrap()
+    );
+
+    let revoked: Vec<_> = parsed.iter_revoked_certificates().collect();
+    assert_eq!(revoked.len(), 3);
+    for (entry, parsed) in crl.revoked_certificates.iter().zip(&revoked) {
+        let serial = serial_number(&entry.certificate_id).unwrap();
+        assert_eq!(parsed.raw_serial(), serial.as_bytes());
+        assert_eq!(
+            parsed.revocation_date.timestamp(),
+            entry.revocation_date as i64
+        );
+    }
+    assert_eq!(
+        revoked[0].reason_code().unwrap().1 .0,
+        CrlReason::KeyCompromise as u8
+    );
+    assert_eq!(
+        revoked[0].invalidity_date().unwrap().1.timestamp(),
+        1759900000
+    );
+    assert_eq!(
+        revoked[1].reason_code().unwrap().1 .0,
+        CrlReason::CertificateHold as u8
+    );
+    assert!(revoked[1].invalidity_date().is_none());
+    // Other has no RFC 5280 code, so the entry carries no reasonCode at all
+    assert!(revoked[2].reason_code().is_none());
+
+    let verified = verify_der(&der, &signing_key().verifying_key()).unwrap();
+    assert_eq!(
+        verified.tbs_cert_list.revoked_certificates.unwrap().len(),
+        3
+    );
+}
+
+#[test]
+fn test_reason_code_mapping() {
+    let cases = [
+        (RevocationReason::KeyCompromise, Some(1)),
+        (RevocationReason::CACompromise, Some(2)),
+        (RevocationReason::AffiliationChanged, Some(3)),
+        (RevocationReason::Superseded, Some(4)),
+        (RevocationReason::CessationOfOperation, Some(5)),
+        (RevocationReason::CertificateHold, Some(6)),
+        (RevocationReason::RemoveFromCRL, Some(8)),
+        (RevocationReason::PrivilegeWithdrawn, Some(9)),
+        (RevocationReason::AACompromise, Some(10)),
+        (RevocationReason::Other("Custom".to_string()), None),
+    ];
+    for (reason, code) in cases {
+        assert_eq!(reason_code(&reason).map(|r| r as u8), code, "{:?}", reason);
+    }
+}
+
+#[test]
+fn test_pem_wraps_der() {
+    let crl = fixture();
+    let options = ExportOptions {
+        issuer_name: Some("CN=StellarCert Issuer,O=Example University".to_string()),
+    };
+    let der = encode_der(&crl, &signing_key(), &options).unwrap();
+    let pem = encode_pem(&crl, &signing_key(), &options).unwrap();
+    assert!(pem.starts_with("-----BEGIN X509 CRL-----\n"));
+
+    let (_, decoded) = x509_parser::pem::parse_x509_pem(pem.as_bytes()).unwrap();
+    assert_eq!(decoded.label, PEM_LABEL);
+    // ed25519 signatures are deterministic, so both encodings carry the same bytes
+    assert_eq!(decoded.contents, der);
+
+    let (_, parsed) = ParsedCrl::from_der(&decoded.contents).unwrap();
+    // RFC 4514 strings list the most specific RDN first, the encoding the reverse
+    assert_eq!(
+        parsed.issuer().to_string(),
+        "O=Example University, CN=StellarCert Issuer"
+    );
+}
+
+#[test]
+fn test_empty_crl_without_aki() {
+    let mut crl = fixture();
+    crl.revoked_certificates.clear();
+    crl.authority_key_identifier = None;
+    crl.crl_number = 1;
+    let key = signing_key();
+    let der = encode_der(&crl, &key, &ExportOptions::default()).unwrap();
+
+    let verified = verify_der(&der, &key.verifying_key()).unwrap();
+    assert!(verified.tbs_cert_list.revoked_certificates.is_none());
+
+    let (_, parsed) = ParsedCrl::from_der(&der).unwrap();
+    assert_eq!(parsed.iter_revoked_certificates().count(), 0);
+    assert_eq!(parsed.crl_number().unwrap().to_string(), "1");
+    let aki = parsed
+        .extensions()
+        .iter()
+        .find_map(|ext| match ext.parsed_extension() {
+            ParsedExtension::AuthorityKeyIdentifier(aki) => Some(aki),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        aki.key_identifier.as_ref().unwrap().0,
+        key_identifier(&key.verifying_key()).as_slice()
+    );
+}
+
+#[test]
+fn test_dates_from_2050_use_generalized_time() {
+    let mut crl = fixture();
+    crl.this_update = 2_524_607_999; // 2049-12-31T23:59:59Z
+    crl.next_update = 2_524_608_000; // 2050-01-01T00:00:00Z
+    let der = encode_der(&crl, &signing_key(), &ExportOptions::default()).unwrap();
+
+    let verified = verify_der(&der, &signing_key().verifying_key()).unwrap();
+    assert!(matches!(
+        verified.tbs_cert_list.this_update,
+        Time::UtcTime(_)
+    ));
+    assert!(matches!(
+        verified.tbs_cert_list.next_update,
+        Some(Time::GeneralTime(_))
+    ));
+
+    let (_, parsed) = ParsedCrl::from_der(&der).unwrap();
+    assert_eq!(parsed.next_update().unwrap().timestamp(), 2_524_608_000);
+}
+
+#[test]
+fn test_signature_is_checked() {
+    let crl = fixture();
+    let der = encode_der(&crl, &signing_key(), &ExportOptions::default()).unwrap();
+
+    let other = SigningKey::from_bytes(&[8u8; 32]);
+    assert!(matches!(
+        verify_der(&der, &other.verifying_key()),
+        Err(ExportError::InvalidSignature)
+    ));
+
+    // Move thisUpdate forward while keeping the original signature
+    let mut tampered = x509_cert::crl::CertificateList::from_der(&der).unwrap();
+    tampered.tbs_cert_list.this_update = tampered.tbs_cert_list.next_update.unwrap();
+    let tampered = tampered.to_der().unwrap();
+    assert!(matches!(
+        verify_der(&tampered, &signing_key().verifying_key()),
+        Err(ExportError::InvalidSignature)
+    ));
+}
+
+#[test]
+fn test_load_signing_key_from_pkcs8() {
+    let key = signing_key();
+    let pem = key.to_pkcs8_pem(LineEnding::LF).unwrap();
+    let der = key.to_pkcs8_der().unwrap();
+
+    assert_eq!(
+        load_signing_key(pem.as_bytes()).unwrap().to_bytes(),
+        key.to_bytes()
+    );
+    assert_eq!(
+        load_signing_key(der.as_bytes()).unwrap().to_bytes(),
+        key.to_bytes()
+    );
+    assert!(matches!(
+        load_signing_key(b"not a key"),
+        Err(ExportError::InvalidKey(_))
+    ));
+}
+
+#[test]
+fn test_invalid_issuer_name() {
+    let options = ExportOptions {
+        issuer_name: Some("not a distinguished name".to_string()),
+    };
+    assert!(matches!(
+        encode_der(&fixture(), &signing_key(), &options),
+        Err(ExportError::InvalidIssuerName(_))
+    ));
+}
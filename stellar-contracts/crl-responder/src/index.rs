@@ -0,0 +1,122 @@
+//! In-memory revocation index built from CRL snapshots and delta CRLs.
+
+use std::collections::HashMap;
+
+use crl_export::{CertificateRevocationList, DeltaCRL, RevokedCertificate};
+use serde::Deserialize;
+
+use crate::ResponderError;
+
+/// Revocation state of one issuer as of `crl_number`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuerState {
+    pub crl_number: u64,
+    pub this_update: u64,
+    pub next_update: u64,
+    pub entries: HashMap<String, RevokedCertificate>,
+}
+
+/// Status of every known issuer, keyed by issuer address.
+#[derive(Clone, Debug, Default)]
+pub struct StatusIndex {
+    issuers: HashMap<String, IssuerState>,
+}
+
+// A snapshot file holds one `get_crl_info` result or a list of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SnapshotFile {
+    Many(Vec<CertificateRevocationList>),
+    One(CertificateRevocationList),
+}
+
+/// Parses a snapshot file: the JSON output of `get_crl_info`, or an array of
+/// such outputs for several issuers.
+pub fn parse_snapshot(json: &str) -> Result<Vec<CertificateRevocationList>, ResponderError> {
+    Ok(match serde_json::from_str(json)? {
+        SnapshotFile::Many(crls) => crls,
+        SnapshotFile::One(crl) => vec![crl],
+    })
+}
+
+/// Parses the JSON output of `get_delta_crl`.
+pub fn parse_delta(json: &str) -> Result<DeltaCRL, ResponderError> {
+    Ok(serde_json::from_str(json)?)
+}
+
+impl StatusIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces an issuer's state with a full CRL. Snapshots older than the
+    /// state already held are rejected.
+    pub fn load_snapshot(&mut self, crl: CertificateRevocationList) -> Result<(), ResponderError> {
+        if let Some(state) = self.issuers.get(&crl.issuer) {
+            if crl.crl_number < state.crl_number {
+                return Err(ResponderError::StaleSnapshot {
+                    issuer: crl.issuer,
+                    current: state.crl_number,
+                    snapshot: crl.crl_number,
+                });
+            }
+        }
+
+        let entries = crl
+            .revoked_certificates
+            .into_iter()
+            .map(|entry| (entry.certificate_id.clone(), entry))
+            .collect();
+        self.issuers.insert(
+            crl.issuer,
+            IssuerState {
+                crl_number: crl.crl_number,
+                this_update: crl.this_update,
+                next_update: crl.next_update,
+                entries,
+            },
+        );
+        Ok(())
+    }
+
+    /// Brings an issuer forward with a delta CRL.
+    ///
+    /// A delta carries the final state of every certificate touched after its
+    /// base, so it applies to any state from `base_crl_number` up to its own
+    /// `crl_number`. Deltas the index has already moved past are ignored.
+    pub fn apply_delta(&mut self, delta: DeltaCRL) -> Result<(), ResponderError> {
+        let state = self
+            .issuers
+            .get_mut(&delta.issuer)
+            .ok_or_else(|| ResponderError::UnknownIssuer(delta.issuer.clone()))?;
+        if delta.base_crl_number > state.crl_number {
+            return Err(ResponderError::MissingChanges {
+                issuer: delta.issuer,
+                current: state.crl_number,
+                base: delta.base_crl_number,
+            });
+        }
+        if delta.crl_number <= state.crl_number {
+            return Ok(());
+        }
+
+        for certificate_id in &delta.removed {
+            state.entries.remove(certificate_id);
+        }
+        for entry in delta.added {
+            state.entries.insert(entry.certificate_id.clone(), entry);
+        }
+        state.crl_number = delta.crl_number;
+        state.this_update = delta.this_update;
+        state.next_update = delta.next_update;
+        Ok(())
+    }
+
+    pub fn issuer(&self, issuer: &str) -> Option<&IssuerState> {
+        self.issuers.get(issuer)
+    }
+
+    pub fn issuers(&self) -> impl Iterator<Item = &str> {
+        self.issuers.keys().map(String::as_str)
+    }
+}
@@ -0,0 +1,83 @@
+//! OCSP-style status responder for the on-chain certificate revocation list.
+//!
+//! The responder keeps an in-memory index built from `get_crl_info` snapshots,
+//! brought forward with `get_delta_crl` results, and answers per-certificate
+//! status requests with good, revoked or unknown. Every answer carries the
+//! issuer's `crl_number` and `this_update` and is signed with the responder's
+//! ed25519 key. The index never talks to the network itself, so a snapshot
+//! file is all it needs.
+
+pub mod index;
+pub mod response;
+pub mod server;
+
+#[cfg(test)]
+mod test;
+
+use core::fmt;
+
+pub use index::{parse_delta, parse_snapshot, IssuerState, StatusIndex};
+pub use response::{CertStatus, Responder, SignedResponse, StatusResponse};
+pub use server::{handle, serve, HttpResponse};
+
+/// Errors raised while building the index or answering requests.
+#[derive(Debug)]
+pub enum ResponderError {
+    /// A snapshot, delta or response is not valid JSON for its type.
+    Json(serde_json::Error),
+    /// A delta names an issuer the index has no snapshot for.
+    UnknownIssuer(String),
+    /// A snapshot is older than the state already indexed for its issuer.
+    StaleSnapshot {
+        issuer: String,
+        current: u64,
+        snapshot: u64,
+    },
+    /// A delta starts after the indexed CRL number, so changes are missing.
+    MissingChanges {
+        issuer: String,
+        current: u64,
+        base: u64,
+    },
+    /// A response signature does not verify.
+    InvalidSignature,
+    /// The HTTP server could not be started.
+    Server(String),
+}
+
+impl fmt::Display for ResponderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponderError::Json(err) => write!(f, "Invalid JSON: {}", err),
+            ResponderError::UnknownIssuer(issuer) => write!(f, "No snapshot for issuer {}", issuer),
+            ResponderError::StaleSnapshot {
+                issuer,
+                current,
+                snapshot,
+            } => write!(
+                f,
+                "Snapshot for {} is at CRL number {}, index is already at {}",
+                issuer, snapshot, current
+            ),
+            ResponderError::MissingChanges {
+                issuer,
+                current,
+                base,
+            } => write!(
+                f,
+                "Delta for {} starts at CRL number {}, index is only at {}",
+                issuer, base, current
+            ),
+            ResponderError::InvalidSignature => write!(f, "Response signature does not verify"),
+            ResponderError::Server(err) => write!(f, "Server error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ResponderError {}
+
+impl From<serde_json::Error> for ResponderError {
+    fn from(err: serde_json::Error) -> Self {
+        ResponderError::Json(err)
+    }
+}
@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+use crl_export::load_signing_key;
+use crl_responder::{parse_delta, parse_snapshot, serve, Responder, StatusIndex};
+
+/// Serve signed certificate status from CRL contract snapshots.
+#[derive(Debug, Parser)]
+#[command(name = "crl-responder", version)]
+struct Args {
+    /// `get_crl_info` output, a single CRL or an array of them (repeatable)
+    #[arg(short, long, required = true)]
+    snapshot: Vec<PathBuf>,
+
+    /// `get_delta_crl` output applied after the snapshots, in order (repeatable)
+    #[arg(short, long)]
+    delta: Vec<PathBuf>,
+
+    /// ed25519 responder key as a PKCS#8 PEM or DER file
+    #[arg(short, long)]
+    key: PathBuf,
+
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    listen: String,
+}
+
+fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    let mut index = StatusIndex::new();
+    for path in &args.snapshot {
+        for crl in parse_snapshot(&fs::read_to_string(path)?)? {
+            index.load_snapshot(crl)?;
+        }
+    }
+    for path in &args.delta {
+        index.apply_delta(parse_delta(&fs::read_to_string(path)?)?)?;
+    }
+
+    let responder = Responder::new(index, load_signing_key(&fs::read(&args.key)?)?);
+    eprintln!(
+        "crl-responder: serving {} issuer(s) on {} with key {}",
+        responder.index().issuers().count(),
+        args.listen,
+        hex::encode(responder.verifying_key().as_bytes())
+    );
+    serve(&responder, &args.listen)?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("crl-responder: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Signed per-certificate status responses.
+
+use crl_export::RevocationReason;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::index::StatusIndex;
+use crate::ResponderError;
+
+/// Certificate status, with the same meaning as in OCSP (RFC 6960).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CertStatus {
+    /// The issuer is known and the certificate is not on its CRL.
+    Good,
+    /// The certificate is on its issuer's CRL, including certificate holds.
+    Revoked,
+    /// The responder has no CRL for the issuer.
+    Unknown,
+}
+
+/// Status of one certificate, as of the responder's copy of the issuer's CRL.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusResponse {
+    pub issuer: String,
+    pub certificate_id: String,
+    pub status: CertStatus,
+    pub reason: Option<RevocationReason>,
+    pub revocation_date: Option<u64>,
+    pub invalidity_date: Option<u64>,
+    /// CRL number, this_update and next_update are absent for unknown issuers.
+    pub crl_number: Option<u64>,
+    pub this_update: Option<u64>,
+    pub next_update: Option<u64>,
+    pub produced_at: u64,
+}
+
+/// A status response and its ed25519 signature.
+///
+/// The signature covers the exact bytes of `tbs_response`, a JSON encoded
+/// [`StatusResponse`], so clients verify before parsing and never have to
+/// re-serialize anything.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedResponse {
+    pub tbs_response: String,
+    /// Hex encoded ed25519 signature over `tbs_response`.
+    pub signature: String,
+    /// Hex encoded ed25519 public key of the responder.
+    pub responder_key: String,
+}
+
+impl SignedResponse {
+    /// Checks the signature against a trusted responder key and returns the
+    /// signed status.
+    pub fn verify(&self, key: &VerifyingKey) -> Result<StatusResponse, ResponderError> {
+        let signature = hex::decode(&self.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or(ResponderError::InvalidSignature)?;
+        key.verify(self.tbs_response.as_bytes(), &signature)
+            .map_err(|_| ResponderError::InvalidSignature)?;
+        Ok(serde_json::from_str(&self.tbs_response)?)
+    }
+}
+
+/// Answers status requests from a [`StatusIndex`].
+pub struct Responder {
+    index: StatusIndex,
+    key: SigningKey,
+}
+
+impl Responder {
+    pub fn new(index: StatusIndex, key: SigningKey) -> Self {
+        Self { index, key }
+    }
+
+    pub fn index(&self) -> &StatusIndex {
+        &self.index
+    }
+
+    pub fn index_mut(&mut self) -> &mut StatusIndex {
+        &mut self.index
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.key.verifying_key()
+    }
+
+    /// Looks up a certificate without signing the answer.
+    pub fn status(&self, issuer: &str, certificate_id: &str, produced_at: u64) -> StatusResponse {
+        let mut response = StatusResponse {
+            issuer: issuer.to_string(),
+            certificate_id: certificate_id.to_string(),
+            status: CertStatus::Unknown,
+            reason: None,
+            revocation_date: None,
+            invalidity_date: None,
+            crl_number: None,
+            this_update: None,
+            next_update: None,
+            produced_at,
+        };
+
+        if let Some(state) = self.index.issuer(issuer) {
+            response.crl_number = Some(state.crl_number);
+            response.this_update = Some(state.this_update);
+            response.next_update = Some(state.next_update);
+            response.status = match state.entries.get(certificate_id) {
+                Some(entry) => {
+                    response.reason = Some(entry.reason.clone());
+                    response.revocation_date = Some(entry.revocation_date);
+                    response.invalidity_date = entry.invalidity_date;
+                    CertStatus::Revoked
+                }
+                None => CertStatus::Good,
+            };
+        }
+        response
+    }
+
+    /// Looks up a certificate and signs the answer with the responder key.
+    pub fn respond(
+        &self,
+        issuer: &str,
+        certificate_id: &str,
+        produced_at: u64,
+    ) -> Result<SignedResponse, ResponderError> {
+        let tbs_response =
+            serde_json::to_string(&self.status(issuer, certificate_id, produced_at))?;
+        let signature = self.key.sign(tbs_response.as_bytes());
+        Ok(SignedResponse {
+            tbs_response,
+            signature: hex::encode(signature.to_bytes()),
+            responder_key: hex::encode(self.key.verifying_key().as_bytes()),
+        })
+    }
+}
@@ -0,0 +1,99 @@
+//! HTTP front end for the responder.
+//!
+//! `GET /status/{issuer}/{certificate_id}` answers with a JSON
+//! [`SignedResponse`](crate::SignedResponse). Certificate ids are
+//! percent-decoded, so ids containing `/` must be sent as `%2F`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::json;
+use tiny_http::{Header, Response, Server};
+
+use crate::response::Responder;
+use crate::ResponderError;
+
+/// Status code and JSON body of a handled request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message }).to_string(),
+        }
+    }
+}
+
+/// Routes one request. Kept free of I/O so it can be tested without a socket.
+pub fn handle(responder: &Responder, method: &str, url: &str, now: u64) -> HttpResponse {
+    let path = url.split('?').next().unwrap_or_default();
+    let Some(rest) = path.strip_prefix("/status/") else {
+        return HttpResponse::error(404, "Not found");
+    };
+    if method != "GET" {
+        return HttpResponse::error(405, "Method not allowed");
+    }
+
+    let segments: Vec<&str> = rest.split('/').collect();
+    let [issuer, certificate_id] = segments[..] else {
+        return HttpResponse::error(404, "Not found");
+    };
+    if issuer.is_empty() || certificate_id.is_empty() {
+        return HttpResponse::error(404, "Not found");
+    }
+    let (Some(issuer), Some(certificate_id)) =
+        (percent_decode(issuer), percent_decode(certificate_id))
+    else {
+        return HttpResponse::error(400, "Invalid percent-encoding");
+    };
+
+    match responder.respond(&issuer, &certificate_id, now) {
+        Ok(signed) => HttpResponse {
+            status: 200,
+            body: serde_json::to_string(&signed).expect("signed response serializes"),
+        },
+        Err(_) => HttpResponse::error(500, "Could not sign response"),
+    }
+}
+
+/// Serves requests on `addr` until the process is stopped.
+pub fn serve(responder: &Responder, addr: &str) -> Result<(), ResponderError> {
+    let server = Server::http(addr).map_err(|e| ResponderError::Server(e.to_string()))?;
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("valid header");
+
+    for request in server.incoming_requests() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let response = handle(responder, request.method().as_str(), request.url(), now);
+        let reply = Response::from_string(response.body)
+            .with_status_code(response.status)
+            .with_header(content_type.clone());
+        // A client that hung up is not the responder's problem
+        let _ = request.respond(reply);
+    }
+    Ok(())
+}
+
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
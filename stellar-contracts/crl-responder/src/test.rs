@@ -0,0 +1,201 @@
+use crl_export::RevocationReason;
+use ed25519_dalek::SigningKey;
+
+use crate::*;
+
+const SNAPSHOT: &str = include_str!("../fixtures/snapshot.json");
+const DELTA: &str = include_str!("../fixtures/delta.json");
+
+const UNIVERSITY: &str = "GDQP2KPQGKIHYJGXNUIYOMHARUARCA7DJT5FO2FFOOKY3B2WSQHG4W37";
+const ACADEMY: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+const NOW: u64 = 1_760_010_000;
+
+fn responder() -> Responder {
+    let mut index = StatusIndex::new();
+    for crl in parse_snapshot(SNAPSHOT).unwrap() {
+        index.load_snapshot(crl).unwrap();
+    }
+    Responder::new(index, SigningKey::from_bytes(&[9u8; 32]))
+}
+
+fn signed_status(responder: &Responder, issuer: &str, certificate_id: &str) -> StatusResponse {
+    responder
+        .respond(issuer, certificate_id, NOW)
+        .unwrap()
+        .verify(&responder.verifying_key())
+        .unwrap()
+}
+
+#[test]
+fn test_snapshot_fixture_statuses() {
+    let responder = responder();
+    assert_eq!(responder.index().issuers().count(), 2);
+
+    let revoked = signed_status(&responder, UNIVERSITY, "CERT-001");
+    assert_eq!(revoked.status, CertStatus::Revoked);
+    assert_eq!(revoked.reason, Some(RevocationReason::KeyCompromise));
+    assert_eq!(revoked.revocation_date, Some(1_759_950_000));
+    assert_eq!(revoked.invalidity_date, Some(1_759_900_000));
+    assert_eq!(revoked.crl_number, Some(5));
+    assert_eq!(revoked.this_update, Some(1_760_000_000));
+    assert_eq!(revoked.next_update, Some(1_760_086_400));
+    assert_eq!(revoked.produced_at, NOW);
+
+    // Holds are reported as revoked with their reason, as OCSP does
+    let held = signed_status(&responder, UNIVERSITY, "CERT-002");
+    assert_eq!(held.status, CertStatus::Revoked);
+    assert_eq!(held.reason, Some(RevocationReason::CertificateHold));
+
+    let good = signed_status(&responder, ACADEMY, "CERT-001");
+    assert_eq!(good.status, CertStatus::Good);
+    assert_eq!(good.reason, None);
+    assert_eq!(good.crl_number, Some(1));
+    assert_eq!(good.this_update, Some(1_759_000_000));
+
+    let unknown = signed_status(&responder, "GUNKNOWNISSUER", "CERT-001");
+    assert_eq!(unknown.status, CertStatus::Unknown);
+    assert_eq!(unknown.crl_number, None);
+    assert_eq!(unknown.this_update, None);
+}
+
+#[test]
+fn test_single_crl_snapshot() {
+    let crls = parse_snapshot(SNAPSHOT).unwrap();
+    let single = serde_json::to_string(&crls[1]).unwrap();
+    let parsed = parse_snapshot(&single).unwrap();
+    assert_eq!(parsed, vec![crls[1].clone()]);
+}
+
+#[test]
+fn test_delta_brings_index_forward() {
+    let mut responder = responder();
+    responder
+        .index_mut()
+        .apply_delta(parse_delta(DELTA).unwrap())
+        .unwrap();
+
+    let released = signed_status(&responder, UNIVERSITY, "CERT-002");
+    assert_eq!(released.status, CertStatus::Good);
+    assert_eq!(released.crl_number, Some(7));
+    assert_eq!(released.this_update, Some(1_760_050_000));
+
+    let added = signed_status(&responder, UNIVERSITY, "CERT-004");
+    assert_eq!(added.status, CertStatus::Revoked);
+    assert_eq!(added.reason, Some(RevocationReason::Superseded));
+
+    // Untouched entries carry over
+    assert_eq!(
+        signed_status(&responder, UNIVERSITY, "CERT-001").status,
+        CertStatus::Revoked
+    );
+
+    // Replaying a delta the index has moved past changes nothing
+    responder
+        .index_mut()
+        .apply_delta(parse_delta(DELTA).unwrap())
+        .unwrap();
+    assert_eq!(responder.index().issuer(UNIVERSITY).unwrap().crl_number, 7);
+    assert_eq!(
+        responder.index().issuer(UNIVERSITY).unwrap().entries.len(),
+        3
+    );
+}
+
+#[test]
+fn test_index_rejects_gaps_and_stale_snapshots() {
+    let mut responder = responder();
+
+    let mut gap = parse_delta(DELTA).unwrap();
+    gap.base_crl_number = 6;
+    gap.crl_number = 8;
+    assert!(matches!(
+        responder.index_mut().apply_delta(gap),
+        Err(ResponderError::MissingChanges {
+            current: 5,
+            base: 6,
+            ..
+        })
+    ));
+
+    let mut unknown = parse_delta(DELTA).unwrap();
+    unknown.issuer = "GUNKNOWNISSUER".to_string();
+    assert!(matches!(
+        responder.index_mut().apply_delta(unknown),
+        Err(ResponderError::UnknownIssuer(_))
+    ));
+
+    let mut stale = parse_snapshot(SNAPSHOT).unwrap().remove(0);
+    stale.crl_number = 4;
+    assert!(matches!(
+        responder.index_mut().load_snapshot(stale),
+        Err(ResponderError::StaleSnapshot {
+            current: 5,
+            snapshot: 4,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_tampered_response_fails_verification() {
+    let responder = responder();
+    let signed = responder.respond(UNIVERSITY, "CERT-001", NOW).unwrap();
+
+    let mut tampered = signed.clone();
+    tampered.tbs_response = tampered.tbs_response.replace("\"revoked\"", "\"good\"");
+    assert!(matches!(
+        tampered.verify(&responder.verifying_key()),
+        Err(ResponderError::InvalidSignature)
+    ));
+
+    let other = SigningKey::from_bytes(&[10u8; 32]);
+    assert!(matches!(
+        signed.verify(&other.verifying_key()),
+        Err(ResponderError::InvalidSignature)
+    ));
+    assert_eq!(
+        signed.responder_key,
+        hex::encode(responder.verifying_key().as_bytes())
+    );
+}
+
+#[test]
+fn test_http_handler() {
+    let responder = responder();
+
+    let response = handle(
+        &responder,
+        "GET",
+        &format!("/status/{}/CERT-001", UNIVERSITY),
+        NOW,
+    );
+    assert_eq!(response.status, 200);
+    let signed: SignedResponse = serde_json::from_str(&response.body).unwrap();
+    let status = signed.verify(&responder.verifying_key()).unwrap();
+    assert_eq!(status.status, CertStatus::Revoked);
+    assert_eq!(status.crl_number, Some(5));
+
+    // Ids containing '/' are percent-encoded
+    let url = format!("/status/{}/DIPLOMA%2F2025%2F17?nonce=1", UNIVERSITY);
+    let response = handle(&responder, "GET", &url, NOW);
+    assert_eq!(response.status, 200);
+    let signed: SignedResponse = serde_json::from_str(&response.body).unwrap();
+    let status = signed.verify(&responder.verifying_key()).unwrap();
+    assert_eq!(status.certificate_id, "DIPLOMA/2025/17");
+    assert_eq!(status.status, CertStatus::Revoked);
+    assert_eq!(
+        status.reason,
+        Some(RevocationReason::Other("Duplicate issuance".to_string()))
+    );
+
+    // Unknown issuers still get a signed answer
+    let response = handle(&responder, "GET", "/status/GUNKNOWNISSUER/CERT-001", NOW);
+    assert_eq!(response.status, 200);
+
+    let url = format!("/status/{}/DIPLOMA/2025/17", UNIVERSITY);
+    assert_eq!(handle(&responder, "GET", &url, NOW).status, 404);
+    assert_eq!(handle(&responder, "GET", "/status/", NOW).status, 404);
+    assert_eq!(handle(&responder, "GET", "/health", NOW).status, 404);
+    assert_eq!(handle(&responder, "POST", "/status/A/B", NOW).status, 405);
+    assert_eq!(handle(&responder, "GET", "/status/A/%G1", NOW).status, 400);
+}
@@ -1,6 +1,43 @@
-#![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec, Bytes, Map};
-use soroban_sdk::iter::UnwrappingIter;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, xdr::ToXdr, Address, Bytes, BytesN, Env, Map, String, Vec,
+};
+
+// Persistent entries live for roughly a year and are topped up whenever touched
+const ENTRY_TTL_THRESHOLD: u32 = 518_400; // ~30 days of ledgers
+const ENTRY_TTL_EXTEND_TO: u32 = 6_312_000; // ~365 days of ledgers
+
+// Certificate ids are hashed from their raw bytes into tree keys
+const MAX_CERTIFICATE_ID_LENGTH: u32 = 128;
+
+// Domain separation between leaf and branch hashes in the status tree
+const LEAF_PREFIX: u8 = 0x00;
+const BRANCH_PREFIX: u8 = 0x01;
+
+// Longest change window a delta CRL will replay; older caches refetch the full list
+const MAX_DELTA_CHANGES: u64 = 500;
+
+// Filtered queries return at most this many entries and read at most this many
+// change log entries per call; callers continue from the returned cursor
+const MAX_QUERY_LIMIT: u32 = 100;
+const MAX_QUERY_SCAN: u64 = 500;
+
+// Storage layout. Consortium-wide settings sit in instance storage; every issuer
+// has its own CRL, and everything that grows with the number of revocations gets
+// its own persistent entry so no single call has to load the whole list.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CRLDataKey {
+    Admin,
+    Issuers,                           // Vec<Address> of registered issuers
+    Metadata(Address),                 // issuer -> CRLMetadata
+    Signers(Address),                  // issuer -> Vec<Address> of delegated CRL signers
+    Entry(Address, String),            // (issuer, certificate_id) -> RevokedCertificate
+    Position(Address, String),         // (issuer, certificate_id) -> slot in the index
+    IndexSlot(Address, u32),           // (issuer, slot) -> certificate_id, dense 0..revoked_count
+    TreeNode(Address, u32, BytesN<32>), // (issuer, depth, key prefix) -> StatusTreeNode
+    Change(Address, u64),              // (issuer, crl_number) -> CRLChange that produced it
+    History(Address, String),          // (issuer, certificate_id) -> Vec<u64> of crl_numbers touching it
+}
 
 // Revocation reason types
 #[contracttype]
@@ -60,6 +97,106 @@ pub struct CertificateRevocationList {
     pub authority_key_identifier: Option<Bytes>, // Optional authority key ID
 }
 
+// CRL header kept separately from the entries
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CRLMetadata {
+    pub issuer: Address,
+    pub this_update: u64,
+    pub next_update: u64,
+    pub crl_number: u64,
+    pub merkle_root: Option<Bytes>,
+    pub authority_key_identifier: Option<Bytes>,
+    pub revoked_count: u32,
+}
+
+// Stored node of the compact sparse Merkle tree over sha256(certificate_id).
+// A subtree holding a single revocation is collapsed into its leaf.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StatusTreeNode {
+    Leaf(BytesN<32>, BytesN<32>), // (key, sha256 of the RevokedCertificate XDR)
+    Branch(BytesN<32>),           // hash of the two children
+}
+
+// Proof that a certificate is on the issuer's CRL
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevocationProof {
+    pub issuer: Address,
+    pub entry: RevokedCertificate,
+    pub siblings: Vec<BytesN<32>>, // Root first
+    pub root: Bytes,
+    pub crl_number: u64,
+}
+
+// Proof that a certificate is absent from the issuer's CRL. The path ends either
+// in an empty subtree or in the leaf of another certificate sharing the prefix.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NonRevocationProof {
+    pub issuer: Address,
+    pub certificate_id: String,
+    pub neighbor_key: Option<Bytes>,
+    pub neighbor_value: Option<Bytes>,
+    pub siblings: Vec<BytesN<32>>, // Root first
+    pub root: Bytes,
+    pub crl_number: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StatusProof {
+    Revoked(RevocationProof),
+    NotRevoked(NonRevocationProof),
+}
+
+// What happened to produce a given crl_number
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CRLChangeKind {
+    Created,                   // Issuer registered, crl_number 1
+    Revoked(RevocationReason), // New entry, or a hold made permanent
+    Removed(RevocationReason), // Released hold, recorded as RemoveFromCRL
+    MetadataUpdated,
+}
+
+// Change log entry, one per crl_number
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CRLChange {
+    pub crl_number: u64,
+    pub kind: CRLChangeKind,
+    pub certificate_id: Option<String>, // None for metadata updates
+    pub timestamp: u64,
+}
+
+// Status of a certificate as of a past moment
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PointInTimeVerification {
+    pub certificate_id: String,
+    pub timestamp: u64,
+    pub status: CertificateStatus,
+    pub retroactive: bool, // Revoked only because invalidity_date precedes the later revocation
+    pub revocation_info: Vec<RevokedCertificate>, // Entry in effect at `timestamp`; empty when Good
+    pub crl_number: u64, // CRL number in effect at `timestamp`, 0 before the issuer's CRL existed
+}
+
+// Entries added and removed between two CRL numbers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeltaCRL {
+    pub issuer: Address,
+    pub base_crl_number: u64,
+    pub crl_number: u64,
+    pub this_update: u64,
+    pub next_update: u64,
+    pub merkle_root: Option<Bytes>,
+    pub added: Vec<RevokedCertificate>,
+    pub removed: Vec<String>,
+}
+
 // Pagination parameters
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -79,12 +216,50 @@ pub struct PaginatedResult {
     pub has_next: bool,
 }
 
+// Filters for query_revoked_certificates; empty or None fields match everything
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CRLQueryFilter {
+    pub issuers: Vec<Address>,          // Empty for every registered issuer
+    pub reasons: Vec<RevocationReason>, // Empty for any reason
+    pub revoked_from: Option<u64>,      // Inclusive lower bound on revocation_date
+    pub revoked_until: Option<u64>,     // Inclusive upper bound on revocation_date
+}
+
+// Position in the change logs of the queried issuers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CRLCursor {
+    pub issuer_index: u32, // Index into the queried issuers
+    pub crl_number: u64,   // Last change of that issuer already read
+}
+
+// One page of a filtered query
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CRLQueryResult {
+    pub data: Vec<RevokedCertificate>,
+    pub crl_numbers: Map<Address, u64>, // crl_number of each queried issuer at query time
+    pub cursor: CRLCursor,              // Pass back with the same filter to continue
+    pub has_next: bool,
+}
+
+// Status of a certificate on its issuer's CRL
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CertificateStatus {
+    Good,
+    OnHold,  // CertificateHold entry, may still be released
+    Revoked, // Permanent revocation
+}
+
 // Verification result
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VerificationResult {
-    pub is_revoked: bool,
-    pub revocation_info: Option<RevokedCertificate>,
+    pub status: CertificateStatus,
+    pub is_revoked: bool, // Listed on the CRL, whether on hold or revoked
+    pub revocation_info: Vec<RevokedCertificate>, // Empty unless revoked
     pub crl_number: u64,
     pub this_update: u64,
 }
@@ -94,291 +269,1151 @@ pub struct CRLContract;
 
 #[contractimpl]
 impl CRLContract {
-    // Initialize the CRL contract
-    pub fn initialize(env: Env, issuer: Address) {
-        issuer.require_auth();
-        
-        let crl = CertificateRevocationList {
+    // Initialize the shared CRL contract with the consortium admin
+    pub fn initialize(env: Env, admin: Address) {
+        admin.require_auth();
+
+        if env.storage().instance().has(&CRLDataKey::Admin) {
+            panic!("Contract already initialized");
+        }
+
+        env.storage().instance().set(&CRLDataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&CRLDataKey::Issuers, &Vec::<Address>::new(&env));
+    }
+
+    // Register an issuer and open an empty CRL for it
+    pub fn register_issuer(env: Env, issuer: Address) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&CRLDataKey::Admin)
+            .expect("Contract not initialized");
+        admin.require_auth();
+
+        let metadata_key = CRLDataKey::Metadata(issuer.clone());
+        if env.storage().persistent().has(&metadata_key) {
+            panic!("Issuer already registered");
+        }
+
+        let metadata = CRLMetadata {
             issuer: issuer.clone(),
             this_update: env.ledger().timestamp(),
             next_update: env.ledger().timestamp() + 86400, // 24 hours
-            revoked_certificates: Vec::new(&env),
-            merkle_root: None,
             crl_number: 1,
+            merkle_root: Some(Self::empty_root(&env).into()),
             authority_key_identifier: None,
+            revoked_count: 0,
         };
-        
-        env.storage().instance().set(&String::from_str(&env, "CRL"), &crl);
-        env.storage().instance().set(&String::from_str(&env, "ISSUER"), &issuer);
+        Self::persist(&env, &metadata_key, &metadata);
+        Self::persist(
+            &env,
+            &CRLDataKey::Signers(issuer.clone()),
+            &Vec::<Address>::new(&env),
+        );
+        Self::record_change(&env, &issuer, &metadata, CRLChangeKind::Created, None);
+
+        let mut issuers: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&CRLDataKey::Issuers)
+            .unwrap_or(Vec::new(&env));
+        issuers.push_back(issuer);
+        env.storage().instance().set(&CRLDataKey::Issuers, &issuers);
+    }
+
+    // Get all issuers that have a CRL in this contract
+    pub fn get_issuers(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&CRLDataKey::Issuers)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // Delegate revocation rights for the issuer's CRL
+    pub fn add_crl_signer(env: Env, issuer: Address, signer: Address) {
+        issuer.require_auth();
+        Self::load_metadata(&env, &issuer);
+
+        let key = CRLDataKey::Signers(issuer.clone());
+        let mut signers: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(&env));
+        if signers.contains(&signer) {
+            panic!("Signer already delegated");
+        }
+        signers.push_back(signer);
+        Self::persist(&env, &key, &signers);
+    }
+
+    // Withdraw a delegated CRL signer
+    pub fn remove_crl_signer(env: Env, issuer: Address, signer: Address) {
+        issuer.require_auth();
+        Self::load_metadata(&env, &issuer);
+
+        let key = CRLDataKey::Signers(issuer.clone());
+        let mut signers: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(&env));
+        let index = signers
+            .first_index_of(&signer)
+            .expect("Signer not delegated");
+        signers.remove(index);
+        Self::persist(&env, &key, &signers);
+    }
+
+    // Get delegated CRL signers of an issuer
+    pub fn get_crl_signers(env: Env, issuer: Address) -> Vec<Address> {
+        Self::load_metadata(&env, &issuer);
+        env.storage()
+            .persistent()
+            .get(&CRLDataKey::Signers(issuer))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // Check whether an address may sign the issuer's CRL
+    pub fn is_crl_signer(env: Env, issuer: Address, signer: Address) -> bool {
+        if signer == issuer {
+            return true;
+        }
+        let signers: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&CRLDataKey::Signers(issuer))
+            .unwrap_or(Vec::new(&env));
+        signers.contains(&signer)
     }
 
-    // Add a certificate to the revocation list
+    // Add a certificate to the issuer's revocation list
     pub fn revoke_certificate(
         env: Env,
+        issuer: Address,
+        signer: Address,
         certificate_id: String,
         reason: RevocationReason,
         invalidity_date: Option<u64>,
     ) {
-        let issuer: Address = env.storage().instance().get(&String::from_str(&env, "ISSUER"))
-            .expect("Contract not initialized");
-        issuer.require_auth();
+        Self::require_crl_signer(&env, &issuer, &signer);
+        let mut metadata = Self::load_metadata(&env, &issuer);
 
-        let mut crl: CertificateRevocationList = env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found");
+        if reason == RevocationReason::RemoveFromCRL {
+            panic!("RemoveFromCRL is not a revocation reason");
+        }
 
-        // Check if already revoked
-        for revoked in crl.revoked_certificates.iter() {
-            if revoked.certificate_id == certificate_id {
+        // Permanent entries are immutable; a hold may only be made permanent
+        let entry_key = CRLDataKey::Entry(issuer.clone(), certificate_id.clone());
+        let existing: Option<RevokedCertificate> = env.storage().persistent().get(&entry_key);
+        if let Some(existing) = &existing {
+            if existing.reason != RevocationReason::CertificateHold
+                || reason == RevocationReason::CertificateHold
+            {
                 panic!("Certificate already revoked");
             }
         }
 
         let revoked_cert = RevokedCertificate {
-            certificate_id,
+            certificate_id: certificate_id.clone(),
             issuer: issuer.clone(),
             revocation_date: env.ledger().timestamp(),
-            reason,
+            reason: reason.clone(),
             invalidity_date,
         };
 
-        crl.revoked_certificates.push_back(revoked_cert);
-        crl.this_update = env.ledger().timestamp();
-        crl.crl_number += 1;
+        let key = Self::certificate_key(&env, &certificate_id);
 
-        // Update Merkle root
-        crl.merkle_root = Some(Self::build_merkle_root(&env, &crl.revoked_certificates));
+        // A hold made permanent keeps its index slot
+        if existing.is_none() {
+            let position = metadata.revoked_count;
+            Self::persist(
+                &env,
+                &CRLDataKey::Position(issuer.clone(), certificate_id.clone()),
+                &position,
+            );
+            Self::persist(
+                &env,
+                &CRLDataKey::IndexSlot(issuer.clone(), position),
+                &certificate_id,
+            );
+            metadata.revoked_count += 1;
+        }
+        Self::persist(&env, &entry_key, &revoked_cert);
 
-        env.storage().instance().set(&String::from_str(&env, "CRL"), &crl);
-    }
+        let root = Self::tree_insert(&env, &issuer, &key, &Self::entry_hash(&env, &revoked_cert));
 
-    // Remove a certificate from revocation list (un-revoke)
-    pub fn unrevoke_certificate(env: Env, certificate_id: String) {
-        let issuer: Address = env.storage().instance().get(&String::from_str(&env, "ISSUER"))
-            .expect("Contract not initialized");
-        issuer.require_auth();
+        metadata.merkle_root = Some(root.into());
+        metadata.this_update = env.ledger().timestamp();
+        metadata.crl_number += 1;
+        Self::record_change(
+            &env,
+            &issuer,
+            &metadata,
+            CRLChangeKind::Revoked(reason),
+            Some(certificate_id),
+        );
+
+        Self::persist(&env, &CRLDataKey::Metadata(issuer), &metadata);
+    }
 
-        let mut crl: CertificateRevocationList = env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found");
+    // Release a certificate hold, removing the entry from the issuer's CRL.
+    // Only CertificateHold entries are reversible.
+    pub fn unrevoke_certificate(
+        env: Env,
+        issuer: Address,
+        signer: Address,
+        certificate_id: String,
+    ) {
+        Self::require_crl_signer(&env, &issuer, &signer);
+        let mut metadata = Self::load_metadata(&env, &issuer);
 
-        let mut found = false;
-        let mut new_list = Vec::new(&env);
-        
-        for revoked in crl.revoked_certificates.iter() {
-            if revoked.certificate_id != certificate_id {
-                new_list.push_back(revoked);
-            } else {
-                found = true;
-            }
+        let entry_key = CRLDataKey::Entry(issuer.clone(), certificate_id.clone());
+        let entry: RevokedCertificate = env
+            .storage()
+            .persistent()
+            .get(&entry_key)
+            .expect("Certificate not found in revocation list");
+        if entry.reason != RevocationReason::CertificateHold {
+            panic!("Only certificate holds can be released");
         }
 
-        if !found {
-            panic!("Certificate not found in revocation list");
+        let position_key = CRLDataKey::Position(issuer.clone(), certificate_id.clone());
+        let position: u32 = env
+            .storage()
+            .persistent()
+            .get(&position_key)
+            .expect("Certificate not found in revocation list");
+
+        // Swap-remove: the last slot moves into the freed one so the index stays dense
+        let last = metadata.revoked_count - 1;
+        if position != last {
+            let moved_id: String = env
+                .storage()
+                .persistent()
+                .get(&CRLDataKey::IndexSlot(issuer.clone(), last))
+                .expect("CRL index corrupted");
+
+            Self::persist(
+                &env,
+                &CRLDataKey::IndexSlot(issuer.clone(), position),
+                &moved_id,
+            );
+            Self::persist(
+                &env,
+                &CRLDataKey::Position(issuer.clone(), moved_id),
+                &position,
+            );
         }
 
-        crl.revoked_certificates = new_list;
-        crl.this_update = env.ledger().timestamp();
-        crl.crl_number += 1;
+        env.storage()
+            .persistent()
+            .remove(&CRLDataKey::IndexSlot(issuer.clone(), last));
+        env.storage().persistent().remove(&position_key);
+        env.storage().persistent().remove(&entry_key);
+        let root = Self::tree_remove(&env, &issuer, &Self::certificate_key(&env, &certificate_id));
 
-        // Update Merkle root
-        crl.merkle_root = Some(Self::build_merkle_root(&env, &crl.revoked_certificates));
+        metadata.revoked_count = last;
+        metadata.merkle_root = Some(root.into());
+        metadata.this_update = env.ledger().timestamp();
+        metadata.crl_number += 1;
+        Self::record_change(
+            &env,
+            &issuer,
+            &metadata,
+            CRLChangeKind::Removed(RevocationReason::RemoveFromCRL),
+            Some(certificate_id),
+        );
 
-        env.storage().instance().set(&String::from_str(&env, "CRL"), &crl);
+        Self::persist(&env, &CRLDataKey::Metadata(issuer), &metadata);
     }
 
-    // Check if a certificate is revoked
-    pub fn is_revoked(env: Env, certificate_id: String) -> bool {
-        let crl: CertificateRevocationList = env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found");
-
-        for revoked in crl.revoked_certificates.iter() {
-            if revoked.certificate_id == certificate_id {
-                return true;
-            }
-        }
-        false
+    // Check if a certificate is revoked by the issuer
+    pub fn is_revoked(env: Env, issuer: Address, certificate_id: String) -> bool {
+        Self::load_metadata(&env, &issuer);
+        env.storage()
+            .persistent()
+            .has(&CRLDataKey::Entry(issuer, certificate_id))
     }
 
     // Get revocation information for a certificate
-    pub fn get_revocation_info(env: Env, certificate_id: String) -> Option<RevokedCertificate> {
-        let crl: CertificateRevocationList = env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found");
-
-        for revoked in crl.revoked_certificates.iter() {
-            if revoked.certificate_id == certificate_id {
-                return Some(revoked);
-            }
-        }
-        None
+    pub fn get_revocation_info(
+        env: Env,
+        issuer: Address,
+        certificate_id: String,
+    ) -> Option<RevokedCertificate> {
+        Self::load_metadata(&env, &issuer);
+        env.storage()
+            .persistent()
+            .get(&CRLDataKey::Entry(issuer, certificate_id))
     }
 
-    // Get paginated list of revoked certificates
-    pub fn get_revoked_certificates(env: Env, pagination: Pagination) -> PaginatedResult {
-        let crl: CertificateRevocationList = env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found");
+    // Get paginated list of the issuer's revoked certificates
+    pub fn get_revoked_certificates(
+        env: Env,
+        issuer: Address,
+        pagination: Pagination,
+    ) -> PaginatedResult {
+        let metadata = Self::load_metadata(&env, &issuer);
 
-        let total = crl.revoked_certificates.len();
-        let start = (pagination.page * pagination.limit) as u32;
-        let end = core::cmp::min(start + pagination.limit, total);
+        let total = metadata.revoked_count;
+        let limit = core::cmp::min(pagination.limit, MAX_QUERY_LIMIT);
+        let start = pagination.page.saturating_mul(limit);
+        let end = core::cmp::min(start.saturating_add(limit), total);
 
         let mut page_data = Vec::new(&env);
         if start < total {
             for i in start..end {
-                page_data.push_back(crl.revoked_certificates.get(i).unwrap());
+                page_data.push_back(Self::entry_at(&env, &issuer, i));
             }
         }
 
         PaginatedResult {
             data: page_data,
-            total: total as u32,
+            total,
             page: pagination.page,
-            limit: pagination.limit,
+            limit,
             has_next: end < total,
         }
     }
 
-    // Get full CRL information
-    pub fn get_crl_info(env: Env) -> CertificateRevocationList {
-        env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found")
+    // Filtered query over one or more issuers' CRLs. Entries come in the order
+    // they were put on the list, read from each issuer's change log, so a cursor
+    // stays valid while certificates are revoked and released between calls:
+    // nothing is skipped or returned twice, except a hold made permanent, which
+    // is returned again with its new reason. Revocations made after the cursor
+    // moved past their issuer are left to get_delta_crl.
+    pub fn query_revoked_certificates(
+        env: Env,
+        filter: CRLQueryFilter,
+        cursor: Option<CRLCursor>,
+        limit: u32,
+    ) -> CRLQueryResult {
+        if limit == 0 {
+            panic!("Limit must be positive");
+        }
+        let limit = core::cmp::min(limit, MAX_QUERY_LIMIT);
+
+        let requested = if filter.issuers.is_empty() {
+            Self::get_issuers(env.clone())
+        } else {
+            filter.issuers.clone()
+        };
+        let mut issuers: Vec<Address> = Vec::new(&env);
+        let mut crl_numbers: Map<Address, u64> = Map::new(&env);
+        for issuer in requested.iter() {
+            if !crl_numbers.contains_key(issuer.clone()) {
+                crl_numbers.set(
+                    issuer.clone(),
+                    Self::load_metadata(&env, &issuer).crl_number,
+                );
+                issuers.push_back(issuer);
+            }
+        }
+
+        let mut cursor = cursor.unwrap_or(CRLCursor {
+            issuer_index: 0,
+            crl_number: 0,
+        });
+        let mut data = Vec::new(&env);
+        let mut scanned = 0u64;
+        while cursor.issuer_index < issuers.len() && data.len() < limit && scanned < MAX_QUERY_SCAN
+        {
+            let issuer = issuers.get_unchecked(cursor.issuer_index);
+            let latest = crl_numbers.get_unchecked(issuer.clone());
+
+            // Revocation dates are the timestamps of the changes that made them,
+            // so the log is sorted by date and the range bounds what to read
+            if let Some(from) = filter.revoked_from.filter(|from| *from > 0) {
+                let before = Self::crl_number_at(&env, &issuer, latest, from - 1);
+                cursor.crl_number = core::cmp::max(cursor.crl_number, before);
+            }
+
+            let mut finished = true;
+            while cursor.crl_number < latest {
+                if data.len() >= limit || scanned >= MAX_QUERY_SCAN {
+                    finished = false;
+                    break;
+                }
+                let number = cursor.crl_number + 1;
+                let change: Option<CRLChange> = env
+                    .storage()
+                    .persistent()
+                    .get(&CRLDataKey::Change(issuer.clone(), number));
+                if let Some(change) = change {
+                    if filter
+                        .revoked_until
+                        .is_some_and(|until| change.timestamp > until)
+                    {
+                        break;
+                    }
+                    if let CRLChange {
+                        kind: CRLChangeKind::Revoked(_),
+                        certificate_id: Some(certificate_id),
+                        ..
+                    } = change
+                    {
+                        if let Some(entry) = Self::listed_by(&env, &issuer, &certificate_id, number)
+                        {
+                            if Self::matches_filter(&filter, &entry) {
+                                data.push_back(entry);
+                            }
+                        }
+                    }
+                }
+                cursor.crl_number = number;
+                scanned += 1;
+            }
+
+            if finished {
+                cursor = CRLCursor {
+                    issuer_index: cursor.issuer_index + 1,
+                    crl_number: 0,
+                };
+            }
+        }
+
+        CRLQueryResult {
+            data,
+            crl_numbers,
+            has_next: cursor.issuer_index < issuers.len(),
+            cursor,
+        }
     }
 
-    // Get CRL verification result with Merkle proof capability
-    pub fn verify_certificate(env: Env, certificate_id: String) -> VerificationResult {
-        let crl: CertificateRevocationList = env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found");
+    // Get entries added and removed since a previously fetched crl_number.
+    // Entries revoked and removed again inside the window only show as removed.
+    pub fn get_delta_crl(env: Env, issuer: Address, since_crl_number: u64) -> DeltaCRL {
+        let metadata = Self::load_metadata(&env, &issuer);
 
-        let revocation_info = Self::get_revocation_info(env.clone(), certificate_id);
-        
-        VerificationResult {
-            is_revoked: revocation_info.is_some(),
-            revocation_info,
-            crl_number: crl.crl_number,
-            this_update: crl.this_update,
+        if since_crl_number > metadata.crl_number {
+            panic!("Unknown CRL number");
+        }
+        if metadata.crl_number - since_crl_number > MAX_DELTA_CHANGES {
+            panic!("Delta too large, fetch the full CRL");
+        }
+
+        // Replay the window, remembering each certificate's final state
+        let mut touched: Map<String, bool> = Map::new(&env);
+        for number in (since_crl_number + 1)..=metadata.crl_number {
+            let change: Option<CRLChange> = env
+                .storage()
+                .persistent()
+                .get(&CRLDataKey::Change(issuer.clone(), number));
+            if let Some(CRLChange {
+                kind,
+                certificate_id: Some(certificate_id),
+                ..
+            }) = change
+            {
+                touched.set(certificate_id, matches!(kind, CRLChangeKind::Revoked(_)));
+            }
+        }
+
+        let mut added = Vec::new(&env);
+        let mut removed = Vec::new(&env);
+        for (certificate_id, revoked) in touched.iter() {
+            if revoked {
+                let entry: RevokedCertificate = env
+                    .storage()
+                    .persistent()
+                    .get(&CRLDataKey::Entry(issuer.clone(), certificate_id))
+                    .expect("CRL index corrupted");
+                added.push_back(entry);
+            } else {
+                removed.push_back(certificate_id);
+            }
+        }
+
+        DeltaCRL {
+            issuer,
+            base_crl_number: since_crl_number,
+            crl_number: metadata.crl_number,
+            this_update: metadata.this_update,
+            next_update: metadata.next_update,
+            merkle_root: metadata.merkle_root,
+            added,
+            removed,
         }
     }
 
-    // Get Merkle root for current CRL
-    pub fn get_merkle_root(env: Env) -> Option<Bytes> {
-        let crl: CertificateRevocationList = env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found");
-        crl.merkle_root
+    // Get a Merkle proof that the certificate is revoked; None if it is not
+    pub fn get_revocation_proof(
+        env: Env,
+        issuer: Address,
+        certificate_id: String,
+    ) -> Option<RevocationProof> {
+        let metadata = Self::load_metadata(&env, &issuer);
+        let entry: RevokedCertificate = env
+            .storage()
+            .persistent()
+            .get(&CRLDataKey::Entry(issuer.clone(), certificate_id.clone()))?;
+
+        let (siblings, _) =
+            Self::tree_path(&env, &issuer, &Self::certificate_key(&env, &certificate_id));
+
+        Some(RevocationProof {
+            issuer,
+            entry,
+            siblings,
+            root: metadata
+                .merkle_root
+                .unwrap_or(Self::empty_root(&env).into()),
+            crl_number: metadata.crl_number,
+        })
+    }
+
+    // Get a Merkle proof that the certificate is not revoked; None if it is
+    pub fn get_non_revocation_proof(
+        env: Env,
+        issuer: Address,
+        certificate_id: String,
+    ) -> Option<NonRevocationProof> {
+        let metadata = Self::load_metadata(&env, &issuer);
+        if env
+            .storage()
+            .persistent()
+            .has(&CRLDataKey::Entry(issuer.clone(), certificate_id.clone()))
+        {
+            return None;
+        }
+
+        let (siblings, terminal) =
+            Self::tree_path(&env, &issuer, &Self::certificate_key(&env, &certificate_id));
+        let (neighbor_key, neighbor_value) = match terminal {
+            Some(StatusTreeNode::Leaf(key, value)) => (Some(key.into()), Some(value.into())),
+            _ => (None, None),
+        };
+
+        Some(NonRevocationProof {
+            issuer,
+            certificate_id,
+            neighbor_key,
+            neighbor_value,
+            siblings,
+            root: metadata
+                .merkle_root
+                .unwrap_or(Self::empty_root(&env).into()),
+            crl_number: metadata.crl_number,
+        })
     }
 
-    // Build Merkle root from revoked certificates
-    fn build_merkle_root(env: &Env, certificates: &Vec<RevokedCertificate>) -> Bytes {
-        if certificates.len() == 0 {
-            // Return hash of empty string for empty list
-            return env.crypto().sha256(&Bytes::from_slice(env, b""));
+    // Check a status proof against a trusted root, e.g. one read from
+    // get_merkle_root or published with a CRL. Needs no contract state.
+    pub fn verify_status_proof(env: Env, root: Bytes, proof: StatusProof) -> bool {
+        let (certificate_id, terminal, siblings) = match &proof {
+            StatusProof::Revoked(p) => {
+                let terminal = match Self::try_certificate_key(&env, &p.entry.certificate_id) {
+                    Some(key) => Some((key, Self::entry_hash(&env, &p.entry))),
+                    None => return false,
+                };
+                (p.entry.certificate_id.clone(), terminal, p.siblings.clone())
+            }
+            StatusProof::NotRevoked(p) => {
+                let terminal = match (&p.neighbor_key, &p.neighbor_value) {
+                    (Some(key), Some(value)) => match (Self::to_hash(key), Self::to_hash(value)) {
+                        (Some(key), Some(value)) => Some((
+                            BytesN::from_array(&env, &key),
+                            BytesN::from_array(&env, &value),
+                        )),
+                        _ => return false,
+                    },
+                    (None, None) => None,
+                    _ => return false,
+                };
+                (p.certificate_id.clone(), terminal, p.siblings.clone())
+            }
+        };
+
+        let key = match Self::try_certificate_key(&env, &certificate_id) {
+            Some(key) => key,
+            None => return false,
+        };
+        if siblings.len() > 256 {
+            return false;
         }
 
-        // Convert certificates to leaf nodes
-        let mut leaves = Vec::new(env);
-        for cert in certificates.iter() {
-            let data = cert.certificate_id.to_bytes();
-            let hash = env.crypto().sha256(&data);
-            leaves.push_back(hash);
+        let mut hash = match &terminal {
+            Some((leaf_key, value)) => {
+                let revoked = matches!(proof, StatusProof::Revoked(_));
+                // A neighbour leaf must differ from the key but sit on its path
+                if !revoked {
+                    if *leaf_key == key {
+                        return false;
+                    }
+                    let common = Self::common_prefix_len(&leaf_key.to_array(), &key.to_array());
+                    if common < siblings.len() {
+                        return false;
+                    }
+                }
+                Self::leaf_node_hash(&env, leaf_key, value)
+            }
+            None => Self::empty_root(&env),
+        };
+
+        let key_bits = key.to_array();
+        for depth in (0..siblings.len()).rev() {
+            let sibling = siblings.get(depth).unwrap();
+            hash = if Self::bit_at(&key_bits, depth) {
+                Self::branch_hash(&env, &sibling, &hash)
+            } else {
+                Self::branch_hash(&env, &hash, &sibling)
+            };
         }
 
-        // Build Merkle tree
-        Self::build_merkle_tree(env, &leaves)
+        Bytes::from_array(&env, &hash.to_array()) == root
     }
 
-    // Build Merkle tree from leaf hashes
-    fn build_merkle_tree(env: &Env, leaves: &Vec<Bytes>) -> Bytes {
-        if leaves.len() == 0 {
-            return env.crypto().sha256(&Bytes::from_slice(env, b""));
+    // Evaluate a certificate's status as of `timestamp` from its revocation,
+    // hold and release history. A permanent revocation whose invalidity_date
+    // is at or before `timestamp` counts even if it was published later.
+    pub fn verify_at(
+        env: Env,
+        issuer: Address,
+        certificate_id: String,
+        timestamp: u64,
+    ) -> PointInTimeVerification {
+        let metadata = Self::load_metadata(&env, &issuer);
+        let current: Option<RevokedCertificate> = env
+            .storage()
+            .persistent()
+            .get(&CRLDataKey::Entry(issuer.clone(), certificate_id.clone()));
+
+        // Latest change to this certificate published by `timestamp`
+        let history: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&CRLDataKey::History(issuer.clone(), certificate_id.clone()))
+            .unwrap_or(Vec::new(&env));
+        let mut in_effect: Option<CRLChange> = None;
+        for crl_number in history.iter() {
+            let change: CRLChange = env
+                .storage()
+                .persistent()
+                .get(&CRLDataKey::Change(issuer.clone(), crl_number))
+                .expect("CRL history corrupted");
+            if change.timestamp > timestamp {
+                break;
+            }
+            in_effect = Some(change);
         }
 
-        let mut current_level = leaves.clone();
-        
-        while current_level.len() > 1 {
-            let mut next_level = Vec::new(env);
-            
-            let len = current_level.len();
-            let mut i = 0;
-            
-            while i < len {
-                let left = current_level.get(i).unwrap();
-                let right = if i + 1 < len {
-                    current_level.get(i + 1).unwrap()
-                } else {
-                    left.clone() // Duplicate last node if odd number
-                };
-                
-                let mut combined = Vec::new(env);
-                combined.push_back(left);
-                combined.push_back(right);
-                let combined_bytes = Self::vec_to_bytes(env, &combined);
-                let parent_hash = env.crypto().sha256(&combined_bytes);
-                next_level.push_back(parent_hash);
-                
-                i += 2;
+        let mut status = CertificateStatus::Good;
+        let mut revocation_info = Vec::new(&env);
+        if let Some(CRLChange {
+            kind: CRLChangeKind::Revoked(reason),
+            timestamp: revoked_at,
+            ..
+        }) = in_effect
+        {
+            status = if reason == RevocationReason::CertificateHold {
+                CertificateStatus::OnHold
+            } else {
+                CertificateStatus::Revoked
+            };
+            // The current entry carries the invalidity date if it is still this revocation
+            let invalidity_date = match &current {
+                Some(entry) if entry.revocation_date == revoked_at && entry.reason == reason => {
+                    entry.invalidity_date
+                }
+                _ => None,
+            };
+            revocation_info.push_back(RevokedCertificate {
+                certificate_id: certificate_id.clone(),
+                issuer: issuer.clone(),
+                revocation_date: revoked_at,
+                reason,
+                invalidity_date,
+            });
+        }
+
+        let mut retroactive = false;
+        if status != CertificateStatus::Revoked {
+            if let Some(entry) = current {
+                let invalid_since = entry.invalidity_date.unwrap_or(entry.revocation_date);
+                if entry.reason != RevocationReason::CertificateHold && invalid_since <= timestamp {
+                    status = CertificateStatus::Revoked;
+                    retroactive = true;
+                    revocation_info = Vec::new(&env);
+                    revocation_info.push_back(entry);
+                }
             }
-            
-            current_level = next_level;
         }
-        
-        current_level.get(0).unwrap()
+
+        PointInTimeVerification {
+            certificate_id,
+            timestamp,
+            status,
+            retroactive,
+            revocation_info,
+            crl_number: Self::crl_number_at(&env, &issuer, metadata.crl_number, timestamp),
+        }
+    }
+
+    // Get the change that produced a crl_number
+    pub fn get_crl_change(env: Env, issuer: Address, crl_number: u64) -> Option<CRLChange> {
+        env.storage()
+            .persistent()
+            .get(&CRLDataKey::Change(issuer, crl_number))
+    }
+
+    // Get CRL header without the entries
+    pub fn get_crl_metadata(env: Env, issuer: Address) -> CRLMetadata {
+        Self::load_metadata(&env, &issuer)
     }
 
-    // Helper function to convert Vec<Bytes> to Bytes
-    fn vec_to_bytes(env: &Env, vec: &Vec<Bytes>) -> Bytes {
-        let mut result = Bytes::new(env);
-        for item in vec.iter() {
-            result.append(&item);
+    // Get the issuer's full CRL. Reads every entry, so large lists should be
+    // fetched through get_revoked_certificates instead.
+    pub fn get_crl_info(env: Env, issuer: Address) -> CertificateRevocationList {
+        let metadata = Self::load_metadata(&env, &issuer);
+
+        let mut revoked_certificates = Vec::new(&env);
+        for i in 0..metadata.revoked_count {
+            revoked_certificates.push_back(Self::entry_at(&env, &issuer, i));
+        }
+
+        CertificateRevocationList {
+            issuer: metadata.issuer,
+            this_update: metadata.this_update,
+            next_update: metadata.next_update,
+            revoked_certificates,
+            merkle_root: metadata.merkle_root,
+            crl_number: metadata.crl_number,
+            authority_key_identifier: metadata.authority_key_identifier,
+        }
+    }
+
+    // Get CRL verification result with Merkle proof capability
+    pub fn verify_certificate(
+        env: Env,
+        issuer: Address,
+        certificate_id: String,
+    ) -> VerificationResult {
+        let metadata = Self::load_metadata(&env, &issuer);
+
+        let mut status = CertificateStatus::Good;
+        let mut revocation_info = Vec::new(&env);
+        if let Some(entry) = Self::get_revocation_info(env.clone(), issuer, certificate_id) {
+            status = if entry.reason == RevocationReason::CertificateHold {
+                CertificateStatus::OnHold
+            } else {
+                CertificateStatus::Revoked
+            };
+            revocation_info.push_back(entry);
+        }
+
+        VerificationResult {
+            status,
+            is_revoked: !revocation_info.is_empty(),
+            revocation_info,
+            crl_number: metadata.crl_number,
+            this_update: metadata.this_update,
         }
-        result
     }
 
-    // Get total count of revoked certificates
-    pub fn get_revoked_count(env: Env) -> u32 {
-        let crl: CertificateRevocationList = env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found");
-        crl.revoked_certificates.len() as u32
+    // Get Merkle root of the issuer's CRL
+    pub fn get_merkle_root(env: Env, issuer: Address) -> Option<Bytes> {
+        Self::load_metadata(&env, &issuer).merkle_root
+    }
+
+    // Get total count of the issuer's revoked certificates
+    pub fn get_revoked_count(env: Env, issuer: Address) -> u32 {
+        Self::load_metadata(&env, &issuer).revoked_count
     }
 
-    // Check if CRL needs update
-    pub fn needs_update(env: Env) -> bool {
-        let crl: CertificateRevocationList = env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found");
-        env.ledger().timestamp() >= crl.next_update
+    // Check if the issuer's CRL needs update
+    pub fn needs_update(env: Env, issuer: Address) -> bool {
+        let metadata = Self::load_metadata(&env, &issuer);
+        env.ledger().timestamp() >= metadata.next_update
     }
 
     // Update CRL metadata
     pub fn update_crl_metadata(
         env: Env,
+        issuer: Address,
+        signer: Address,
         next_update: Option<u64>,
         authority_key_identifier: Option<Bytes>,
     ) {
-        let issuer: Address = env.storage().instance().get(&String::from_str(&env, "ISSUER"))
-            .expect("Contract not initialized");
-        issuer.require_auth();
-
-        let mut crl: CertificateRevocationList = env.storage().instance()
-            .get(&String::from_str(&env, "CRL"))
-            .expect("CRL not found");
+        Self::require_crl_signer(&env, &issuer, &signer);
+        let mut metadata = Self::load_metadata(&env, &issuer);
 
         if let Some(next) = next_update {
-            crl.next_update = next;
+            metadata.next_update = next;
         }
 
         if let Some(aki) = authority_key_identifier {
-            crl.authority_key_identifier = Some(aki);
+            metadata.authority_key_identifier = Some(aki);
+        }
+
+        metadata.this_update = env.ledger().timestamp();
+        metadata.crl_number += 1;
+        Self::record_change(
+            &env,
+            &issuer,
+            &metadata,
+            CRLChangeKind::MetadataUpdated,
+            None,
+        );
+
+        Self::persist(&env, &CRLDataKey::Metadata(issuer), &metadata);
+    }
+}
+
+impl CRLContract {
+    // The issuer itself or one of its delegated signers must authorize
+    fn require_crl_signer(env: &Env, issuer: &Address, signer: &Address) {
+        signer.require_auth();
+        if !Self::is_crl_signer(env.clone(), issuer.clone(), signer.clone()) {
+            panic!("Not an authorized CRL signer");
+        }
+    }
+
+    fn load_metadata(env: &Env, issuer: &Address) -> CRLMetadata {
+        env.storage()
+            .persistent()
+            .get(&CRLDataKey::Metadata(issuer.clone()))
+            .expect("Issuer not registered")
+    }
+
+    fn record_change(
+        env: &Env,
+        issuer: &Address,
+        metadata: &CRLMetadata,
+        kind: CRLChangeKind,
+        certificate_id: Option<String>,
+    ) {
+        let change = CRLChange {
+            crl_number: metadata.crl_number,
+            kind,
+            certificate_id,
+            timestamp: metadata.this_update,
+        };
+        Self::persist(
+            env,
+            &CRLDataKey::Change(issuer.clone(), metadata.crl_number),
+            &change,
+        );
+
+        if let Some(certificate_id) = change.certificate_id {
+            let key = CRLDataKey::History(issuer.clone(), certificate_id);
+            let mut history: Vec<u64> = env
+                .storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or(Vec::new(env));
+            history.push_back(metadata.crl_number);
+            Self::persist(env, &key, &history);
+        }
+    }
+
+    // The current entry for the certificate, if change `crl_number` is what put
+    // it on the list. Older revocations of a released or converted hold are stale.
+    fn listed_by(
+        env: &Env,
+        issuer: &Address,
+        certificate_id: &String,
+        crl_number: u64,
+    ) -> Option<RevokedCertificate> {
+        let entry: RevokedCertificate = env
+            .storage()
+            .persistent()
+            .get(&CRLDataKey::Entry(issuer.clone(), certificate_id.clone()))?;
+        let history: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&CRLDataKey::History(issuer.clone(), certificate_id.clone()))
+            .expect("CRL index corrupted");
+        if history.last() == Some(crl_number) {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    fn matches_filter(filter: &CRLQueryFilter, entry: &RevokedCertificate) -> bool {
+        (filter.reasons.is_empty() || filter.reasons.contains(&entry.reason))
+            && filter
+                .revoked_from
+                .is_none_or(|from| entry.revocation_date >= from)
+            && filter
+                .revoked_until
+                .is_none_or(|until| entry.revocation_date <= until)
+    }
+
+    // Highest crl_number published at or before `timestamp`. Change timestamps
+    // never decrease, so this is a binary search over the change log.
+    fn crl_number_at(env: &Env, issuer: &Address, latest: u64, timestamp: u64) -> u64 {
+        let (mut low, mut high) = (0u64, latest);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            let published_at = env
+                .storage()
+                .persistent()
+                .get::<_, CRLChange>(&CRLDataKey::Change(issuer.clone(), mid))
+                .map(|change| change.timestamp)
+                .unwrap_or(0);
+            if published_at <= timestamp {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low
+    }
+
+    fn persist<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(
+        env: &Env,
+        key: &CRLDataKey,
+        value: &V,
+    ) {
+        env.storage().persistent().set(key, value);
+        env.storage()
+            .persistent()
+            .extend_ttl(key, ENTRY_TTL_THRESHOLD, ENTRY_TTL_EXTEND_TO);
+    }
+
+    fn entry_at(env: &Env, issuer: &Address, slot: u32) -> RevokedCertificate {
+        let certificate_id: String = env
+            .storage()
+            .persistent()
+            .get(&CRLDataKey::IndexSlot(issuer.clone(), slot))
+            .expect("CRL index corrupted");
+        env.storage()
+            .persistent()
+            .get(&CRLDataKey::Entry(issuer.clone(), certificate_id))
+            .expect("CRL index corrupted")
+    }
+
+    // Leaf value commits to the full entry, not just the id
+    fn entry_hash(env: &Env, entry: &RevokedCertificate) -> BytesN<32> {
+        env.crypto().sha256(&entry.clone().to_xdr(env)).to_bytes()
+    }
+
+    // Tree key is sha256 over the raw UTF-8 bytes of the certificate id
+    fn certificate_key(env: &Env, certificate_id: &String) -> BytesN<32> {
+        Self::try_certificate_key(env, certificate_id).expect("Certificate id too long")
+    }
+
+    fn try_certificate_key(env: &Env, certificate_id: &String) -> Option<BytesN<32>> {
+        let len = certificate_id.len();
+        if len > MAX_CERTIFICATE_ID_LENGTH {
+            return None;
+        }
+        let mut buf = [0u8; MAX_CERTIFICATE_ID_LENGTH as usize];
+        certificate_id.copy_into_slice(&mut buf[..len as usize]);
+        Some(
+            env.crypto()
+                .sha256(&Bytes::from_slice(env, &buf[..len as usize]))
+                .to_bytes(),
+        )
+    }
+
+    fn to_hash(bytes: &Bytes) -> Option<[u8; 32]> {
+        if bytes.len() != 32 {
+            return None;
+        }
+        let mut out = [0u8; 32];
+        bytes.copy_into_slice(&mut out);
+        Some(out)
+    }
+
+    // Root of an empty tree
+    fn empty_root(env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &[0u8; 32])
+    }
+
+    fn leaf_node_hash(env: &Env, key: &BytesN<32>, value: &BytesN<32>) -> BytesN<32> {
+        let mut data = Bytes::from_array(env, &[LEAF_PREFIX]);
+        data.append(&Bytes::from_array(env, &key.to_array()));
+        data.append(&Bytes::from_array(env, &value.to_array()));
+        env.crypto().sha256(&data).to_bytes()
+    }
+
+    fn branch_hash(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+        let mut data = Bytes::from_array(env, &[BRANCH_PREFIX]);
+        data.append(&Bytes::from_array(env, &left.to_array()));
+        data.append(&Bytes::from_array(env, &right.to_array()));
+        env.crypto().sha256(&data).to_bytes()
+    }
+
+    // Bits are read most significant first
+    fn bit_at(key: &[u8; 32], depth: u32) -> bool {
+        (key[(depth / 8) as usize] >> (7 - depth % 8)) & 1 == 1
+    }
+
+    fn common_prefix_len(a: &[u8; 32], b: &[u8; 32]) -> u32 {
+        for depth in 0..256 {
+            if Self::bit_at(a, depth) != Self::bit_at(b, depth) {
+                return depth;
+            }
+        }
+        256
+    }
+
+    // First `depth` bits of the key, optionally with the last of them flipped
+    fn key_prefix(env: &Env, key: &[u8; 32], depth: u32, flip_last: bool) -> BytesN<32> {
+        let mut prefix = [0u8; 32];
+        for i in 0..depth {
+            let bit = Self::bit_at(key, i) != (flip_last && i + 1 == depth);
+            if bit {
+                prefix[(i / 8) as usize] |= 1 << (7 - i % 8);
+            }
+        }
+        BytesN::from_array(env, &prefix)
+    }
+
+    fn node_key(env: &Env, issuer: &Address, key: &[u8; 32], depth: u32) -> CRLDataKey {
+        CRLDataKey::TreeNode(
+            issuer.clone(),
+            depth,
+            Self::key_prefix(env, key, depth, false),
+        )
+    }
+
+    fn sibling_key(env: &Env, issuer: &Address, key: &[u8; 32], depth: u32) -> CRLDataKey {
+        CRLDataKey::TreeNode(
+            issuer.clone(),
+            depth,
+            Self::key_prefix(env, key, depth, true),
+        )
+    }
+
+    fn load_node(env: &Env, node_key: &CRLDataKey) -> Option<StatusTreeNode> {
+        env.storage().persistent().get(node_key)
+    }
+
+    fn node_hash(env: &Env, node: &Option<StatusTreeNode>) -> BytesN<32> {
+        match node {
+            Some(StatusTreeNode::Leaf(key, value)) => Self::leaf_node_hash(env, key, value),
+            Some(StatusTreeNode::Branch(hash)) => hash.clone(),
+            None => Self::empty_root(env),
         }
+    }
 
-        crl.this_update = env.ledger().timestamp();
-        crl.crl_number += 1;
+    // Sibling hashes from the root down to the node where the key's path ends
+    fn tree_path(
+        env: &Env,
+        issuer: &Address,
+        key: &BytesN<32>,
+    ) -> (Vec<BytesN<32>>, Option<StatusTreeNode>) {
+        let bits = key.to_array();
+        let mut siblings = Vec::new(env);
+        let mut depth = 0;
+        loop {
+            match Self::load_node(env, &Self::node_key(env, issuer, &bits, depth)) {
+                Some(StatusTreeNode::Branch(_)) => {
+                    depth += 1;
+                    let sibling =
+                        Self::load_node(env, &Self::sibling_key(env, issuer, &bits, depth));
+                    siblings.push_back(Self::node_hash(env, &sibling));
+                }
+                terminal => return (siblings, terminal),
+            }
+        }
+    }
 
-        env.storage().instance().set(&String::from_str(&env, "CRL"), &crl);
+    // Recompute the branches above `depth` along the key's path: O(depth)
+    fn rehash_path(env: &Env, issuer: &Address, bits: &[u8; 32], depth: u32) -> BytesN<32> {
+        let mut hash = Self::node_hash(
+            env,
+            &Self::load_node(env, &Self::node_key(env, issuer, bits, depth)),
+        );
+        for level in (0..depth).rev() {
+            let sibling = Self::node_hash(
+                env,
+                &Self::load_node(env, &Self::sibling_key(env, issuer, bits, level + 1)),
+            );
+            hash = if Self::bit_at(bits, level) {
+                Self::branch_hash(env, &sibling, &hash)
+            } else {
+                Self::branch_hash(env, &hash, &sibling)
+            };
+            Self::persist(
+                env,
+                &Self::node_key(env, issuer, bits, level),
+                &StatusTreeNode::Branch(hash.clone()),
+            );
+        }
+        hash
     }
-}
\ No newline at end of file
+
+    fn tree_insert(
+        env: &Env,
+        issuer: &Address,
+        key: &BytesN<32>,
+        value: &BytesN<32>,
+    ) -> BytesN<32> {
+        let bits = key.to_array();
+        let leaf = StatusTreeNode::Leaf(key.clone(), value.clone());
+        let mut depth = 0;
+        loop {
+            let node_key = Self::node_key(env, issuer, &bits, depth);
+            match Self::load_node(env, &node_key) {
+                Some(StatusTreeNode::Branch(_)) => depth += 1,
+                Some(StatusTreeNode::Leaf(other_key, other_value)) if other_key != *key => {
+                    // Split: both leaves move below the first bit where the keys differ
+                    let other_bits = other_key.to_array();
+                    depth = Self::common_prefix_len(&bits, &other_bits) + 1;
+                    Self::persist(
+                        env,
+                        &Self::node_key(env, issuer, &other_bits, depth),
+                        &StatusTreeNode::Leaf(other_key, other_value),
+                    );
+                    Self::persist(env, &Self::node_key(env, issuer, &bits, depth), &leaf);
+                    return Self::rehash_path(env, issuer, &bits, depth);
+                }
+                _ => {
+                    Self::persist(env, &node_key, &leaf);
+                    return Self::rehash_path(env, issuer, &bits, depth);
+                }
+            }
+        }
+    }
+
+    fn tree_remove(env: &Env, issuer: &Address, key: &BytesN<32>) -> BytesN<32> {
+        let bits = key.to_array();
+        let mut depth = 0;
+        loop {
+            match Self::load_node(env, &Self::node_key(env, issuer, &bits, depth)) {
+                Some(StatusTreeNode::Branch(_)) => depth += 1,
+                Some(StatusTreeNode::Leaf(leaf_key, _)) if leaf_key == *key => break,
+                _ => panic!("CRL index corrupted"),
+            }
+        }
+        env.storage()
+            .persistent()
+            .remove(&Self::node_key(env, issuer, &bits, depth));
+
+        // Collapse: a lone leaf moves up while its parent would hold nothing else
+        let mut current: Option<StatusTreeNode> = None;
+        while depth > 0 {
+            let sibling_key = Self::sibling_key(env, issuer, &bits, depth);
+            let sibling = Self::load_node(env, &sibling_key);
+            let lifted = match (&current, &sibling) {
+                (None, Some(StatusTreeNode::Leaf(..))) => {
+                    env.storage().persistent().remove(&sibling_key);
+                    sibling
+                }
+                (Some(StatusTreeNode::Leaf(..)), None) => {
+                    env.storage()
+                        .persistent()
+                        .remove(&Self::node_key(env, issuer, &bits, depth));
+                    current
+                }
+                _ => break,
+            };
+            depth -= 1;
+            Self::persist(
+                env,
+                &Self::node_key(env, issuer, &bits, depth),
+                lifted.as_ref().unwrap(),
+            );
+            current = lifted;
+        }
+
+        Self::rehash_path(env, issuer, &bits, depth)
+    }
+}
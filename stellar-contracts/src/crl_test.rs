@@ -1,6 +1,13 @@
 #![cfg(test)]
+extern crate std;
 use super::crl::*;
-use soroban_sdk::{Env, testutils::Address as _, Address, String, Vec};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    xdr::ToXdr,
+    Address, Bytes, Env, String,
+};
+use std::string::ToString;
+use std::{format, vec};
 
 #[test]
 fn test_crl_initialization() {
@@ -8,12 +15,14 @@ fn test_crl_initialization() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     
     env.mock_all_auths();
-    client.initialize(&issuer);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
 
-    let crl = client.get_crl_info();
+    let crl = client.get_crl_info(&issuer);
     assert_eq!(crl.issuer, issuer);
     assert_eq!(crl.revoked_certificates.len(), 0);
     assert_eq!(crl.crl_number, 1);
@@ -26,21 +35,23 @@ fn test_revoke_certificate() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     let cert_id = String::from_str(&env, "CERT-001");
     let reason = RevocationReason::KeyCompromise;
     
     env.mock_all_auths();
-    client.initialize(&issuer);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
     
     // Revoke certificate
-    client.revoke_certificate(&cert_id, &reason, &None);
+    client.revoke_certificate(&issuer, &issuer, &cert_id, &reason, &None);
     
     // Check if revoked
-    assert!(client.is_revoked(&cert_id));
+    assert!(client.is_revoked(&issuer, &cert_id));
     
     // Get revocation info
-    let info = client.get_revocation_info(&cert_id);
+    let info = client.get_revocation_info(&issuer, &cert_id);
     assert!(info.is_some());
     let info = info.unwrap();
     assert_eq!(info.certificate_id, cert_id);
@@ -48,7 +59,7 @@ fn test_revoke_certificate() {
     assert_eq!(info.issuer, issuer);
     
     // Check CRL updated
-    let crl = client.get_crl_info();
+    let crl = client.get_crl_info(&issuer);
     assert_eq!(crl.revoked_certificates.len(), 1);
     assert_eq!(crl.crl_number, 2); // Should increment
 }
@@ -59,30 +70,32 @@ fn test_multiple_revocations() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     
     env.mock_all_auths();
-    client.initialize(&issuer);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
     
     // Revoke multiple certificates
     let cert1 = String::from_str(&env, "CERT-001");
     let cert2 = String::from_str(&env, "CERT-002");
     let cert3 = String::from_str(&env, "CERT-003");
     
-    client.revoke_certificate(&cert1, &RevocationReason::KeyCompromise, &None);
-    client.revoke_certificate(&cert2, &RevocationReason::CACompromise, &None);
-    client.revoke_certificate(&cert3, &RevocationReason::Superseded, &None);
+    client.revoke_certificate(&issuer, &issuer, &cert1, &RevocationReason::KeyCompromise, &None);
+    client.revoke_certificate(&issuer, &issuer, &cert2, &RevocationReason::CACompromise, &None);
+    client.revoke_certificate(&issuer, &issuer, &cert3, &RevocationReason::Superseded, &None);
     
     // Check all are revoked
-    assert!(client.is_revoked(&cert1));
-    assert!(client.is_revoked(&cert2));
-    assert!(client.is_revoked(&cert3));
+    assert!(client.is_revoked(&issuer, &cert1));
+    assert!(client.is_revoked(&issuer, &cert2));
+    assert!(client.is_revoked(&issuer, &cert3));
     
     // Check count
-    assert_eq!(client.get_revoked_count(), 3);
+    assert_eq!(client.get_revoked_count(&issuer), 3);
     
     // Check CRL number incremented properly
-    let crl = client.get_crl_info();
+    let crl = client.get_crl_info(&issuer);
     assert_eq!(crl.crl_number, 4);
 }
 
@@ -92,24 +105,32 @@ fn test_unrevoke_certificate() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     let cert_id = String::from_str(&env, "CERT-001");
     
     env.mock_all_auths();
-    client.initialize(&issuer);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
     
-    // Revoke first
-    client.revoke_certificate(&cert_id, &RevocationReason::KeyCompromise, &None);
-    assert!(client.is_revoked(&cert_id));
-    assert_eq!(client.get_revoked_count(), 1);
+    // Put on hold first
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &cert_id,
+        &RevocationReason::CertificateHold,
+        &None,
+    );
+    assert!(client.is_revoked(&issuer, &cert_id));
+    assert_eq!(client.get_revoked_count(&issuer), 1);
     
-    // Unrevoke
-    client.unrevoke_certificate(&cert_id);
-    assert!(!client.is_revoked(&cert_id));
-    assert_eq!(client.get_revoked_count(), 0);
+    // Release the hold
+    client.unrevoke_certificate(&issuer, &issuer, &cert_id);
+    assert!(!client.is_revoked(&issuer, &cert_id));
+    assert_eq!(client.get_revoked_count(&issuer), 0);
     
     // CRL number should increment
-    let crl = client.get_crl_info();
+    let crl = client.get_crl_info(&issuer);
     assert_eq!(crl.crl_number, 3);
 }
 
@@ -119,11 +140,12 @@ fn test_revocation_reasons() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
-    let cert_id = String::from_str(&env, "CERT-001");
     
     env.mock_all_auths();
-    client.initialize(&issuer);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
     
     // Test different revocation reasons
     let reasons = vec![
@@ -140,8 +162,8 @@ fn test_revocation_reasons() {
     
     for (i, reason) in reasons.iter().enumerate() {
         let cert_id = String::from_str(&env, &format!("CERT-{:03}", i));
-        client.revoke_certificate(&cert_id, reason, &None);
-        let info = client.get_revocation_info(&cert_id).unwrap();
+        client.revoke_certificate(&issuer, &issuer, &cert_id, reason, &None);
+        let info = client.get_revocation_info(&issuer, &cert_id).unwrap();
         assert_eq!(&info.reason, reason);
     }
 }
@@ -152,35 +174,43 @@ fn test_paginated_retrieval() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     
     env.mock_all_auths();
-    client.initialize(&issuer);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
     
     // Add 15 certificates
     for i in 0..15 {
         let cert_id = String::from_str(&env, &format!("CERT-{:03}", i));
-        client.revoke_certificate(&cert_id, &RevocationReason::KeyCompromise, &None);
+        client.revoke_certificate(
+            &issuer,
+            &issuer,
+            &cert_id,
+            &RevocationReason::KeyCompromise,
+            &None,
+        );
     }
     
     // Test pagination
-    let page1 = client.get_revoked_certificates(&Pagination { page: 0, limit: 5 });
+    let page1 = client.get_revoked_certificates(&issuer, &Pagination { page: 0, limit: 5 });
     assert_eq!(page1.data.len(), 5);
     assert_eq!(page1.total, 15);
     assert_eq!(page1.page, 0);
     assert_eq!(page1.limit, 5);
     assert!(page1.has_next);
     
-    let page2 = client.get_revoked_certificates(&Pagination { page: 1, limit: 5 });
+    let page2 = client.get_revoked_certificates(&issuer, &Pagination { page: 1, limit: 5 });
     assert_eq!(page2.data.len(), 5);
     assert!(page2.has_next);
     
-    let page3 = client.get_revoked_certificates(&Pagination { page: 2, limit: 5 });
+    let page3 = client.get_revoked_certificates(&issuer, &Pagination { page: 2, limit: 5 });
     assert_eq!(page3.data.len(), 5);
     assert!(!page3.has_next);
     
     // Test page beyond end
-    let page4 = client.get_revoked_certificates(&Pagination { page: 3, limit: 5 });
+    let page4 = client.get_revoked_certificates(&issuer, &Pagination { page: 3, limit: 5 });
     assert_eq!(page4.data.len(), 0);
     assert!(!page4.has_next);
 }
@@ -191,23 +221,31 @@ fn test_verify_certificate() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     let cert_id = String::from_str(&env, "CERT-001");
     
     env.mock_all_auths();
-    client.initialize(&issuer);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
     
     // Verify non-revoked certificate
-    let result = client.verify_certificate(&cert_id);
+    let result = client.verify_certificate(&issuer, &cert_id);
     assert!(!result.is_revoked);
-    assert!(result.revocation_info.is_none());
+    assert!(result.revocation_info.is_empty());
     assert_eq!(result.crl_number, 1);
     
     // Revoke and verify
-    client.revoke_certificate(&cert_id, &RevocationReason::KeyCompromise, &None);
-    let result = client.verify_certificate(&cert_id);
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &cert_id,
+        &RevocationReason::KeyCompromise,
+        &None,
+    );
+    let result = client.verify_certificate(&issuer, &cert_id);
     assert!(result.is_revoked);
-    assert!(result.revocation_info.is_some());
+    assert!(result.revocation_info.len() == 1);
     assert_eq!(result.crl_number, 2);
 }
 
@@ -217,27 +255,35 @@ fn test_merkle_root_updates() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     
     env.mock_all_auths();
-    client.initialize(&issuer);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
     
-    let initial_root = client.get_merkle_root();
+    let initial_root = client.get_merkle_root(&issuer);
     assert!(initial_root.is_some());
     
     // Add certificates and check root changes
-    let root1 = client.get_merkle_root().unwrap();
-    client.revoke_certificate(&String::from_str(&env, "CERT-001"), &RevocationReason::KeyCompromise, &None);
-    let root2 = client.get_merkle_root().unwrap();
+    let root1 = client.get_merkle_root(&issuer).unwrap();
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &String::from_str(&env, "CERT-001"),
+        &RevocationReason::CertificateHold,
+        &None,
+    );
+    let root2 = client.get_merkle_root(&issuer).unwrap();
     assert_ne!(root1, root2);
     
-    client.revoke_certificate(&String::from_str(&env, "CERT-002"), &RevocationReason::KeyCompromise, &None);
-    let root3 = client.get_merkle_root().unwrap();
+    client.revoke_certificate(&issuer, &issuer, &String::from_str(&env, "CERT-002"), &RevocationReason::KeyCompromise, &None);
+    let root3 = client.get_merkle_root(&issuer).unwrap();
     assert_ne!(root2, root3);
     
     // Remove certificate and check root changes again
-    client.unrevoke_certificate(&String::from_str(&env, "CERT-001"));
-    let root4 = client.get_merkle_root().unwrap();
+    client.unrevoke_certificate(&issuer, &issuer, &String::from_str(&env, "CERT-001"));
+    let root4 = client.get_merkle_root(&issuer).unwrap();
     assert_ne!(root3, root4);
 }
 
@@ -247,24 +293,26 @@ fn test_crl_metadata_updates() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     
     env.mock_all_auths();
-    client.initialize(&issuer);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
     
-    let original_crl = client.get_crl_info();
+    let original_crl = client.get_crl_info(&issuer);
     let original_update = original_crl.next_update;
     
     // Update next update time
     let new_update = original_update + 3600; // 1 hour later
-    client.update_crl_metadata(&Some(new_update), &None);
+    client.update_crl_metadata(&issuer, &issuer, &Some(new_update), &None);
     
-    let updated_crl = client.get_crl_info();
+    let updated_crl = client.get_crl_info(&issuer);
     assert_eq!(updated_crl.next_update, new_update);
     assert_eq!(updated_crl.crl_number, 2); // Should increment
     
     // Test needs_update
-    assert!(!client.needs_update()); // Should not need update yet
+    assert!(!client.needs_update(&issuer)); // Should not need update yet
 }
 
 #[test]
@@ -274,15 +322,23 @@ fn test_duplicate_revocation_panics() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     let cert_id = String::from_str(&env, "CERT-001");
     
     env.mock_all_auths();
-    client.initialize(&issuer);
-    client.revoke_certificate(&cert_id, &RevocationReason::KeyCompromise, &None);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+    client.revoke_certificate(&issuer, &issuer, &cert_id, &RevocationReason::KeyCompromise, &None);
     
     // Try to revoke again - should panic
-    client.revoke_certificate(&cert_id, &RevocationReason::KeyCompromise, &None);
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &cert_id,
+        &RevocationReason::KeyCompromise,
+        &None,
+    );
 }
 
 #[test]
@@ -292,12 +348,915 @@ fn test_unrevoke_nonexistent_panics() {
     let contract_id = env.register_contract(None, CRLContract);
     let client = CRLContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     let cert_id = String::from_str(&env, "CERT-001");
     
     env.mock_all_auths();
-    client.initialize(&issuer);
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
     
     // Try to unrevoke non-existent certificate - should panic
-    client.unrevoke_certificate(&cert_id);
-}
\ No newline at end of file
+    client.unrevoke_certificate(&issuer, &issuer, &cert_id);
+}
+
+fn hash_parts(env: &Env, parts: &[&[u8]]) -> [u8; 32] {
+    let mut data = Bytes::new(env);
+    for part in parts {
+        data.extend_from_slice(part);
+    }
+    env.crypto().sha256(&data).to_bytes().to_array()
+}
+
+fn bit_at(key: &[u8; 32], depth: usize) -> bool {
+    (key[depth / 8] >> (7 - depth % 8)) & 1 == 1
+}
+
+// Builds the compact sparse Merkle tree from scratch over sorted leaves
+fn build_subtree(env: &Env, leaves: &[([u8; 32], [u8; 32])], depth: usize) -> [u8; 32] {
+    match leaves.len() {
+        0 => [0u8; 32],
+        1 => hash_parts(env, &[&[0u8], &leaves[0].0, &leaves[0].1]),
+        _ => {
+            let split = leaves
+                .iter()
+                .position(|(key, _)| bit_at(key, depth))
+                .unwrap_or(leaves.len());
+            let left = build_subtree(env, &leaves[..split], depth + 1);
+            let right = build_subtree(env, &leaves[split..], depth + 1);
+            hash_parts(env, &[&[1u8], &left, &right])
+        }
+    }
+}
+
+fn reference_merkle_root(env: &Env, client: &CRLContractClient, issuer: &Address) -> Bytes {
+    let entries = client.get_crl_info(issuer).revoked_certificates;
+
+    let mut leaves = std::vec::Vec::new();
+    for entry in entries.iter() {
+        let id = entry.certificate_id.to_string();
+        let key = hash_parts(env, &[id.as_bytes()]);
+        let value = env
+            .crypto()
+            .sha256(&entry.to_xdr(env))
+            .to_bytes()
+            .to_array();
+        leaves.push((key, value));
+    }
+    leaves.sort();
+
+    Bytes::from_array(env, &build_subtree(env, &leaves, 0))
+}
+
+#[test]
+fn test_incremental_merkle_root_matches_rebuild() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+    assert_eq!(
+        client.get_merkle_root(&issuer).unwrap(),
+        reference_merkle_root(&env, &client, &issuer)
+    );
+
+    for i in 0..11 {
+        let cert_id = String::from_str(&env, &format!("CERT-{:03}", i));
+        client.revoke_certificate(
+            &issuer,
+            &issuer,
+            &cert_id,
+            &RevocationReason::CertificateHold,
+            &None,
+        );
+        assert_eq!(
+            client.get_merkle_root(&issuer).unwrap(),
+            reference_merkle_root(&env, &client, &issuer)
+        );
+    }
+
+    // Removing from the middle moves the last entry into the freed slot
+    client.unrevoke_certificate(&issuer, &issuer, &String::from_str(&env, "CERT-003"));
+    let page = client.get_revoked_certificates(&issuer, &Pagination { page: 0, limit: 20 });
+    assert_eq!(page.total, 10);
+    assert_eq!(
+        page.data.get(3).unwrap().certificate_id,
+        String::from_str(&env, "CERT-010")
+    );
+    assert_eq!(
+        client.get_merkle_root(&issuer).unwrap(),
+        reference_merkle_root(&env, &client, &issuer)
+    );
+
+    client.unrevoke_certificate(&issuer, &issuer, &String::from_str(&env, "CERT-010"));
+    client.unrevoke_certificate(&issuer, &issuer, &String::from_str(&env, "CERT-000"));
+    assert_eq!(client.get_revoked_count(&issuer), 8);
+    assert!(!client.is_revoked(&issuer, &String::from_str(&env, "CERT-010")));
+    assert!(client.is_revoked(&issuer, &String::from_str(&env, "CERT-009")));
+    assert_eq!(
+        client.get_merkle_root(&issuer).unwrap(),
+        reference_merkle_root(&env, &client, &issuer)
+    );
+}
+
+#[test]
+fn test_issuers_have_independent_crls() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let university = Address::generate(&env);
+    let academy = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&university);
+    client.register_issuer(&academy);
+    assert_eq!(client.get_issuers().len(), 2);
+
+    let cert_id = String::from_str(&env, "CERT-001");
+    client.revoke_certificate(
+        &university,
+        &university,
+        &cert_id,
+        &RevocationReason::KeyCompromise,
+        &None,
+    );
+
+    // Same certificate id under another issuer is unaffected
+    assert!(client.is_revoked(&university, &cert_id));
+    assert!(!client.is_revoked(&academy, &cert_id));
+    assert_eq!(client.get_crl_metadata(&university).crl_number, 2);
+    assert_eq!(client.get_crl_metadata(&academy).crl_number, 1);
+    assert_ne!(
+        client.get_merkle_root(&university),
+        client.get_merkle_root(&academy)
+    );
+
+    client.revoke_certificate(
+        &academy,
+        &academy,
+        &cert_id,
+        &RevocationReason::Superseded,
+        &None,
+    );
+    assert_eq!(
+        client
+            .get_revocation_info(&academy, &cert_id)
+            .unwrap()
+            .reason,
+        RevocationReason::Superseded
+    );
+    assert_eq!(
+        client
+            .get_revocation_info(&university, &cert_id)
+            .unwrap()
+            .reason,
+        RevocationReason::KeyCompromise
+    );
+
+    client.update_crl_metadata(&academy, &academy, &Some(1_000_000), &None);
+    assert_eq!(client.get_crl_info(&academy).next_update, 1_000_000);
+    assert_eq!(client.get_crl_info(&university).next_update, 86400);
+}
+
+#[test]
+fn test_delegated_crl_signers() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let signer = Address::generate(&env);
+    let cert_id = String::from_str(&env, "CERT-001");
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+
+    assert!(client.is_crl_signer(&issuer, &issuer));
+    assert!(!client.is_crl_signer(&issuer, &signer));
+
+    client.add_crl_signer(&issuer, &signer);
+    assert_eq!(client.get_crl_signers(&issuer).len(), 1);
+
+    client.revoke_certificate(
+        &issuer,
+        &signer,
+        &cert_id,
+        &RevocationReason::CertificateHold,
+        &None,
+    );
+    let info = client.get_revocation_info(&issuer, &cert_id).unwrap();
+    assert_eq!(info.issuer, issuer);
+
+    client.unrevoke_certificate(&issuer, &signer, &cert_id);
+    assert!(!client.is_revoked(&issuer, &cert_id));
+
+    client.remove_crl_signer(&issuer, &signer);
+    assert!(!client.is_crl_signer(&issuer, &signer));
+    let result = client.try_revoke_certificate(
+        &issuer,
+        &signer,
+        &cert_id,
+        &RevocationReason::KeyCompromise,
+        &None,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "Issuer not registered")]
+fn test_unregistered_issuer_panics() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &String::from_str(&env, "CERT-001"),
+        &RevocationReason::KeyCompromise,
+        &None,
+    );
+}
+
+#[test]
+fn test_delta_crl() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let cert1 = String::from_str(&env, "CERT-001");
+    let cert2 = String::from_str(&env, "CERT-002");
+    let cert3 = String::from_str(&env, "CERT-003");
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &cert1,
+        &RevocationReason::KeyCompromise,
+        &None,
+    );
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &cert2,
+        &RevocationReason::CertificateHold,
+        &None,
+    );
+    let cached = client.get_crl_metadata(&issuer).crl_number;
+    assert_eq!(cached, 3);
+
+    // Nothing changed since the cached number
+    let delta = client.get_delta_crl(&issuer, &cached);
+    assert_eq!(delta.added.len(), 0);
+    assert_eq!(delta.removed.len(), 0);
+    assert_eq!(delta.crl_number, cached);
+
+    client.unrevoke_certificate(&issuer, &issuer, &cert2);
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &cert3,
+        &RevocationReason::Superseded,
+        &None,
+    );
+    client.update_crl_metadata(&issuer, &issuer, &Some(500_000), &None);
+
+    let delta = client.get_delta_crl(&issuer, &cached);
+    assert_eq!(delta.base_crl_number, 3);
+    assert_eq!(delta.crl_number, 6);
+    assert_eq!(delta.next_update, 500_000);
+    assert_eq!(delta.merkle_root, client.get_merkle_root(&issuer));
+    assert_eq!(delta.added.len(), 1);
+    assert_eq!(delta.added.get(0).unwrap().certificate_id, cert3);
+    assert_eq!(delta.removed, soroban_sdk::vec![&env, cert2.clone()]);
+
+    // From the start, cert2 was added and removed inside the window
+    let delta = client.get_delta_crl(&issuer, &1);
+    assert_eq!(delta.added.len(), 2);
+    assert_eq!(delta.removed, soroban_sdk::vec![&env, cert2.clone()]);
+
+    let change = client.get_crl_change(&issuer, &4).unwrap();
+    assert_eq!(
+        change.kind,
+        CRLChangeKind::Removed(RevocationReason::RemoveFromCRL)
+    );
+    assert_eq!(change.certificate_id, Some(cert2));
+    assert_eq!(
+        client.get_crl_change(&issuer, &6).unwrap().kind,
+        CRLChangeKind::MetadataUpdated
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unknown CRL number")]
+fn test_delta_crl_from_future_number_panics() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+
+    client.get_delta_crl(&issuer, &5);
+}
+
+#[test]
+fn test_revocation_and_non_revocation_proofs() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+
+    // Empty tree: every certificate has a non-revocation proof
+    let unknown = String::from_str(&env, "CERT-999");
+    let proof = client.get_non_revocation_proof(&issuer, &unknown).unwrap();
+    let root = client.get_merkle_root(&issuer).unwrap();
+    assert!(client.verify_status_proof(&root, &StatusProof::NotRevoked(proof)));
+
+    for i in 0..20 {
+        let cert_id = String::from_str(&env, &format!("CERT-{:03}", i));
+        client.revoke_certificate(
+            &issuer,
+            &issuer,
+            &cert_id,
+            &RevocationReason::KeyCompromise,
+            &None,
+        );
+    }
+    let root = client.get_merkle_root(&issuer).unwrap();
+
+    for i in 0..20 {
+        let cert_id = String::from_str(&env, &format!("CERT-{:03}", i));
+        assert!(client.get_non_revocation_proof(&issuer, &cert_id).is_none());
+
+        let proof = client.get_revocation_proof(&issuer, &cert_id).unwrap();
+        assert_eq!(proof.root, root);
+        assert_eq!(proof.entry.certificate_id, cert_id);
+        assert!(client.verify_status_proof(&root, &StatusProof::Revoked(proof.clone())));
+
+        // Tampering with the entry breaks the proof
+        let mut forged = proof;
+        forged.entry.reason = RevocationReason::Superseded;
+        assert!(!client.verify_status_proof(&root, &StatusProof::Revoked(forged)));
+    }
+
+    for i in 20..40 {
+        let cert_id = String::from_str(&env, &format!("CERT-{:03}", i));
+        assert!(client.get_revocation_proof(&issuer, &cert_id).is_none());
+        let proof = client.get_non_revocation_proof(&issuer, &cert_id).unwrap();
+        assert!(client.verify_status_proof(&root, &StatusProof::NotRevoked(proof.clone())));
+
+        // A non-revocation proof cannot be replayed for a revoked certificate
+        let mut forged = proof;
+        forged.certificate_id = String::from_str(&env, "CERT-005");
+        assert!(!client.verify_status_proof(&root, &StatusProof::NotRevoked(forged)));
+    }
+
+    // Proofs are bound to the root they were produced for
+    let stale = client.get_non_revocation_proof(&issuer, &unknown).unwrap();
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &unknown,
+        &RevocationReason::KeyCompromise,
+        &None,
+    );
+    let new_root = client.get_merkle_root(&issuer).unwrap();
+    assert!(client.verify_status_proof(&root, &StatusProof::NotRevoked(stale.clone())));
+    assert!(!client.verify_status_proof(&new_root, &StatusProof::NotRevoked(stale)));
+    assert_eq!(new_root, reference_merkle_root(&env, &client, &issuer));
+}
+
+#[test]
+fn test_certificate_hold_lifecycle() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let held = String::from_str(&env, "CERT-001");
+    let escalated = String::from_str(&env, "CERT-002");
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &held,
+        &RevocationReason::CertificateHold,
+        &None,
+    );
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &escalated,
+        &RevocationReason::CertificateHold,
+        &None,
+    );
+
+    let result = client.verify_certificate(&issuer, &held);
+    assert_eq!(result.status, CertificateStatus::OnHold);
+    assert!(result.is_revoked);
+
+    // A hold may be made permanent, keeping its place in the index
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &escalated,
+        &RevocationReason::KeyCompromise,
+        &Some(0),
+    );
+    assert_eq!(client.get_revoked_count(&issuer), 2);
+    let result = client.verify_certificate(&issuer, &escalated);
+    assert_eq!(result.status, CertificateStatus::Revoked);
+    assert_eq!(
+        result.revocation_info.get(0).unwrap().reason,
+        RevocationReason::KeyCompromise
+    );
+    assert_eq!(
+        client.get_crl_change(&issuer, &4).unwrap().kind,
+        CRLChangeKind::Revoked(RevocationReason::KeyCompromise)
+    );
+    assert_eq!(
+        client.get_merkle_root(&issuer).unwrap(),
+        reference_merkle_root(&env, &client, &issuer)
+    );
+
+    // Releasing a hold is logged as RemoveFromCRL
+    client.unrevoke_certificate(&issuer, &issuer, &held);
+    assert_eq!(
+        client.verify_certificate(&issuer, &held).status,
+        CertificateStatus::Good
+    );
+    assert_eq!(
+        client.get_crl_change(&issuer, &5).unwrap().kind,
+        CRLChangeKind::Removed(RevocationReason::RemoveFromCRL)
+    );
+
+    // Permanent revocations are immutable
+    assert!(client
+        .try_unrevoke_certificate(&issuer, &issuer, &escalated)
+        .is_err());
+    assert!(client
+        .try_revoke_certificate(
+            &issuer,
+            &issuer,
+            &escalated,
+            &RevocationReason::CertificateHold,
+            &None
+        )
+        .is_err());
+    assert!(client
+        .try_revoke_certificate(
+            &issuer,
+            &issuer,
+            &escalated,
+            &RevocationReason::Superseded,
+            &None
+        )
+        .is_err());
+    assert_eq!(
+        client.verify_certificate(&issuer, &escalated).status,
+        CertificateStatus::Revoked
+    );
+
+    // RemoveFromCRL is only ever a log entry
+    assert!(client
+        .try_revoke_certificate(
+            &issuer,
+            &issuer,
+            &held,
+            &RevocationReason::RemoveFromCRL,
+            &None
+        )
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "Only certificate holds can be released")]
+fn test_unrevoke_permanent_revocation_panics() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let cert_id = String::from_str(&env, "CERT-001");
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &cert_id,
+        &RevocationReason::KeyCompromise,
+        &None,
+    );
+
+    client.unrevoke_certificate(&issuer, &issuer, &cert_id);
+}
+
+#[test]
+fn test_verify_at_point_in_time() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let held = String::from_str(&env, "CERT-001");
+    let compromised = String::from_str(&env, "CERT-002");
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+
+    // Hold at 2000, released at 3000
+    env.ledger().with_mut(|l| l.timestamp = 2_000);
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &held,
+        &RevocationReason::CertificateHold,
+        &None,
+    );
+    env.ledger().with_mut(|l| l.timestamp = 3_000);
+    client.unrevoke_certificate(&issuer, &issuer, &held);
+
+    // Revoked at 5000, but the key was compromised at 4000
+    env.ledger().with_mut(|l| l.timestamp = 5_000);
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &compromised,
+        &RevocationReason::KeyCompromise,
+        &Some(4_000),
+    );
+
+    let before = client.verify_at(&issuer, &held, &500);
+    assert_eq!(before.status, CertificateStatus::Good);
+    assert_eq!(before.crl_number, 0);
+
+    let result = client.verify_at(&issuer, &held, &1_500);
+    assert_eq!(result.status, CertificateStatus::Good);
+    assert_eq!(result.crl_number, 1);
+
+    let result = client.verify_at(&issuer, &held, &2_500);
+    assert_eq!(result.status, CertificateStatus::OnHold);
+    assert_eq!(result.crl_number, 2);
+    assert_eq!(
+        result.revocation_info.get(0).unwrap().revocation_date,
+        2_000
+    );
+
+    let result = client.verify_at(&issuer, &held, &3_000);
+    assert_eq!(result.status, CertificateStatus::Good);
+    assert_eq!(result.crl_number, 3);
+    assert!(result.revocation_info.is_empty());
+
+    // Before the invalidity date the certificate was fine
+    let result = client.verify_at(&issuer, &compromised, &3_500);
+    assert_eq!(result.status, CertificateStatus::Good);
+    assert!(!result.retroactive);
+
+    // Between compromise and publication it counts as revoked in hindsight
+    let result = client.verify_at(&issuer, &compromised, &4_500);
+    assert_eq!(result.status, CertificateStatus::Revoked);
+    assert!(result.retroactive);
+    assert_eq!(result.crl_number, 3);
+    assert_eq!(
+        result.revocation_info.get(0).unwrap().invalidity_date,
+        Some(4_000)
+    );
+
+    let result = client.verify_at(&issuer, &compromised, &6_000);
+    assert_eq!(result.status, CertificateStatus::Revoked);
+    assert!(!result.retroactive);
+    assert_eq!(result.crl_number, 4);
+    assert_eq!(
+        result.revocation_info.get(0).unwrap().reason,
+        RevocationReason::KeyCompromise
+    );
+}
+
+#[test]
+fn test_query_revoked_certificates_filters() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer_a = Address::generate(&env);
+    let issuer_b = Address::generate(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer_a);
+    client.register_issuer(&issuer_b);
+
+    let revocations = [
+        (&issuer_a, "CERT-A1", RevocationReason::KeyCompromise, 2_000),
+        (&issuer_b, "CERT-B1", RevocationReason::KeyCompromise, 2_500),
+        (&issuer_a, "CERT-A2", RevocationReason::Superseded, 3_000),
+        (
+            &issuer_b,
+            "CERT-B2",
+            RevocationReason::Other(String::from_str(&env, "Audit")),
+            3_500,
+        ),
+        (
+            &issuer_a,
+            "CERT-A3",
+            RevocationReason::CertificateHold,
+            4_000,
+        ),
+    ];
+    for (issuer, id, reason, timestamp) in revocations.iter() {
+        env.ledger().with_mut(|l| l.timestamp = *timestamp);
+        client.revoke_certificate(issuer, issuer, &String::from_str(&env, id), reason, &None);
+    }
+
+    let ids = |result: &CRLQueryResult| -> std::vec::Vec<std::string::String> {
+        result
+            .data
+            .iter()
+            .map(|entry| entry.certificate_id.to_string())
+            .collect()
+    };
+    let no_filter = CRLQueryFilter {
+        issuers: soroban_sdk::Vec::new(&env),
+        reasons: soroban_sdk::Vec::new(&env),
+        revoked_from: None,
+        revoked_until: None,
+    };
+
+    // Issuers in registration order, each in the order its entries were listed
+    let all = client.query_revoked_certificates(&no_filter, &None, &10);
+    assert_eq!(
+        ids(&all),
+        vec!["CERT-A1", "CERT-A2", "CERT-A3", "CERT-B1", "CERT-B2"]
+    );
+    assert!(!all.has_next);
+    assert_eq!(all.crl_numbers.get(issuer_a.clone()), Some(4));
+    assert_eq!(all.crl_numbers.get(issuer_b.clone()), Some(3));
+
+    let mut by_reason = no_filter.clone();
+    by_reason.reasons.push_back(RevocationReason::KeyCompromise);
+    let result = client.query_revoked_certificates(&by_reason, &None, &10);
+    assert_eq!(ids(&result), vec!["CERT-A1", "CERT-B1"]);
+
+    let mut by_issuer = by_reason.clone();
+    by_issuer.issuers.push_back(issuer_b.clone());
+    by_issuer
+        .reasons
+        .push_back(RevocationReason::Other(String::from_str(&env, "Audit")));
+    let result = client.query_revoked_certificates(&by_issuer, &None, &10);
+    assert_eq!(ids(&result), vec!["CERT-B1", "CERT-B2"]);
+    assert_eq!(result.crl_numbers.len(), 1);
+
+    let mut by_date = no_filter.clone();
+    by_date.revoked_from = Some(2_500);
+    by_date.revoked_until = Some(3_500);
+    let result = client.query_revoked_certificates(&by_date, &None, &10);
+    assert_eq!(ids(&result), vec!["CERT-A2", "CERT-B1", "CERT-B2"]);
+}
+
+#[test]
+fn test_query_cursor_survives_concurrent_changes() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+
+    let id = |i: u32| String::from_str(&env, &format!("CERT-{:03}", i));
+    for i in 0..6 {
+        let reason = if i == 1 || i == 4 {
+            RevocationReason::CertificateHold
+        } else {
+            RevocationReason::Superseded
+        };
+        client.revoke_certificate(&issuer, &issuer, &id(i), &reason, &None);
+    }
+
+    let mut filter = CRLQueryFilter {
+        issuers: soroban_sdk::Vec::new(&env),
+        reasons: soroban_sdk::Vec::new(&env),
+        revoked_from: None,
+        revoked_until: None,
+    };
+    filter.issuers.push_back(issuer.clone());
+
+    let mut seen = std::vec::Vec::new();
+    let page = client.query_revoked_certificates(&filter, &None, &2);
+    assert!(page.has_next);
+    seen.extend(page.data.iter().map(|entry| entry.certificate_id));
+
+    // A release before the cursor, a release after it, a hold made permanent
+    // after it and a new revocation: index-based pages would shift here
+    client.unrevoke_certificate(&issuer, &issuer, &id(1));
+    client.unrevoke_certificate(&issuer, &issuer, &id(4));
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &id(6),
+        &RevocationReason::KeyCompromise,
+        &None,
+    );
+
+    let mut cursor = page.cursor;
+    loop {
+        let page = client.query_revoked_certificates(&filter, &Some(cursor), &2);
+        assert_eq!(
+            page.crl_numbers.get(issuer.clone()),
+            Some(client.get_crl_metadata(&issuer).crl_number)
+        );
+        seen.extend(page.data.iter().map(|entry| entry.certificate_id));
+        cursor = page.cursor;
+        if !page.has_next {
+            break;
+        }
+    }
+
+    let expected: std::vec::Vec<String> = [0, 1, 2, 3, 5, 6].iter().map(|i| id(*i)).collect();
+    assert_eq!(seen, expected);
+
+    // A hold made permanent after it was returned shows up again with its new reason
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &id(7),
+        &RevocationReason::CertificateHold,
+        &None,
+    );
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &id(8),
+        &RevocationReason::Superseded,
+        &None,
+    );
+    let page = client.query_revoked_certificates(&filter, &None, &6);
+    assert!(page.has_next);
+    assert_eq!(page.data.get(5).unwrap().certificate_id, id(7));
+    client.revoke_certificate(
+        &issuer,
+        &issuer,
+        &id(7),
+        &RevocationReason::KeyCompromise,
+        &None,
+    );
+
+    let page = client.query_revoked_certificates(&filter, &Some(page.cursor), &6);
+    assert!(!page.has_next);
+    assert_eq!(page.data.len(), 2);
+    assert_eq!(page.data.get(0).unwrap().certificate_id, id(8));
+    assert_eq!(page.data.get(1).unwrap().certificate_id, id(7));
+    assert_eq!(
+        page.data.get(1).unwrap().reason,
+        RevocationReason::KeyCompromise
+    );
+}
+
+#[test]
+fn test_pagination_with_large_page_does_not_overflow() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+    for i in 0..3 {
+        let cert_id = String::from_str(&env, &format!("CERT-{:03}", i));
+        client.revoke_certificate(
+            &issuer,
+            &issuer,
+            &cert_id,
+            &RevocationReason::Superseded,
+            &None,
+        );
+    }
+
+    let result = client.get_revoked_certificates(
+        &issuer,
+        &Pagination {
+            page: u32::MAX,
+            limit: 2,
+        },
+    );
+    assert_eq!(result.data.len(), 0);
+    assert_eq!(result.total, 3);
+    assert!(!result.has_next);
+
+    let result = client.get_revoked_certificates(
+        &issuer,
+        &Pagination {
+            page: 1,
+            limit: u32::MAX,
+        },
+    );
+    assert_eq!(result.data.len(), 0);
+    assert!(!result.has_next);
+}
+
+#[test]
+fn test_revoked_certificates_page_size_is_clamped() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, CRLContract);
+    let client = CRLContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_issuer(&issuer);
+    for i in 0..105 {
+        // Each call gets a fresh budget; only the list size matters here
+        env.budget().reset_default();
+        let cert_id = String::from_str(&env, &format!("CERT-{:03}", i));
+        client.revoke_certificate(
+            &issuer,
+            &issuer,
+            &cert_id,
+            &RevocationReason::Superseded,
+            &None,
+        );
+    }
+
+    // Pages hold at most 100 entries, like query_revoked_certificates
+    env.budget().reset_default();
+    let page0 = client.get_revoked_certificates(
+        &issuer,
+        &Pagination {
+            page: 0,
+            limit: 1000,
+        },
+    );
+    assert_eq!(page0.data.len(), 100);
+    assert_eq!(page0.limit, 100);
+    assert_eq!(page0.total, 105);
+    assert!(page0.has_next);
+
+    // Later pages are offset by the clamped limit
+    let page1 = client.get_revoked_certificates(
+        &issuer,
+        &Pagination {
+            page: 1,
+            limit: 1000,
+        },
+    );
+    assert_eq!(page1.data.len(), 5);
+    assert_eq!(
+        page1.data.get(0).unwrap().certificate_id,
+        String::from_str(&env, "CERT-100")
+    );
+    assert!(!page1.has_next);
+}
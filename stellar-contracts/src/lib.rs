@@ -1,12 +1,27 @@
 #![no_std]
+use core::cmp::Ordering;
 use soroban_sdk::{
-    contract, contractimpl, contracttype, Address, Bytes, BytesN, Env, String, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, xdr::FromXdr,
+    Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Val, Vec,
 };
 
 const MAX_BATCH_SIZE: u32 = 50;
 const BASE_VERIFICATION_COST: u64 = 10;
 const COST_PER_CERTIFICATE: u64 = 5;
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec, symbol_short};
+const TRANSFER_EXPIRY_SECONDS: u64 = 7 * 24 * 60 * 60; // Pending transfers lapse after 7 days
+const UPGRADE_EXPIRY_SECONDS: u64 = 30 * 24 * 60 * 60; // Unexecuted upgrades lapse after 30 days
+// Domain-separation prefixes for batch Merkle trees (see MERKLE_README.md)
+const MERKLE_LEAF_PREFIX: u8 = 0x00;
+const MERKLE_NODE_PREFIX: u8 = 0x01;
+const MAX_MERKLE_DEPTH: u32 = 32;
+// Batch roots and revocation bitmaps outlive the instance; keep them for about a year
+const BATCH_TTL_THRESHOLD: u32 = 518_400; // ~30 days of ledgers
+const BATCH_TTL_EXTEND_TO: u32 = 6_312_000; // ~365 days of ledgers
+const MAX_LINEAGE_LENGTH: u32 = 64; // Bound on upgrade chains walked by lineage queries
+const MAX_VERSION_LABEL_LENGTH: usize = 64; // Pre-release and build labels
+// "MAJOR.MINOR.PATCH-pre+build" with u32 fields and maximum-length labels
+const MAX_VERSION_STRING_LENGTH: usize = 3 * 10 + 2 + 2 * (1 + MAX_VERSION_LABEL_LENGTH);
+const MAX_CERTIFICATE_ID_LENGTH: usize = 128;
 
 // Soroban event emission - topics must be a tuple of up to 4 elements
 // We'll emit events using env.events().publish()
@@ -18,7 +33,8 @@ pub struct CertificateVersion {
     pub major: u32,
     pub minor: u32,
     pub patch: u32,
-    pub build: Option<String>,
+    pub pre_release: Option<String>, // e.g. "beta.1"; ranks below the release
+    pub build: Option<String>,       // Build metadata, ignored for precedence
 }
 
 /// Upgrade path validation rule
@@ -40,11 +56,15 @@ pub struct ArchivedCertificate {
     pub version: CertificateVersion,
     pub archived_at: u64,
     pub archived_by: Address,
-    pub original_data: String, // Serialized original certificate data
-    pub reason: String,        // Reason for archiving
+    pub certificate: Certificate, // Full certificate as it was when archived
+    pub reason: String,           // Reason for archiving
 }
 
 /// Certificate upgrade request
+///
+/// Metadata hashes are accepted as `BytesN<32>` but stored as `Bytes`, since an
+/// `Option<BytesN<32>>` field has no `ScVal` conversion under the SDK's
+/// testutils. Every stored hash is therefore exactly 32 bytes.
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct UpgradeRequest {
@@ -57,9 +77,45 @@ pub struct UpgradeRequest {
     pub requested_at: u64,
     pub approved_at: Option<u64>,
     pub completed_at: Option<u64>,
+    pub expires_at: u64,         // Pending/approved requests lapse after this time
     pub status: UpgradeStatus,
+    pub failure_code: Option<u32>, // CertificateError code when execution failed
     pub migration_data: Option<String>, // Additional migration data
     pub notes: Option<String>,   // Optional notes about the upgrade
+    pub new_metadata_uri: Option<String>,      // Metadata the upgraded certificate points to
+    pub new_metadata_hash: Option<Bytes>,      // SHA-256 of the new metadata (32 bytes)
+    pub migration_script_hash: Option<String>, // Migration script applied on execution
+}
+
+/// Result of executing an upgrade
+///
+/// Failures after the upgrade started are reported here rather than as an
+/// error, because a failed call would also roll back the failure record.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpgradeOutcome {
+    pub status: UpgradeStatus,              // Completed or Failed
+    pub new_certificate_id: Option<String>, // Set when the upgrade completed
+    pub failure_code: Option<u32>,          // CertificateError code when it failed
+}
+
+/// Record of how a certificate's metadata changed during an upgrade
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetadataMigration {
+    pub upgrade_id: String,
+    pub from_certificate_id: String,
+    pub to_certificate_id: String,
+    pub from_version: CertificateVersion,
+    pub to_version: CertificateVersion,
+    pub old_metadata_uri: String,
+    pub new_metadata_uri: String,
+    pub old_metadata_hash: Option<Bytes>,
+    pub new_metadata_hash: Option<Bytes>,
+    pub metadata_changed: bool,
+    pub migration_script_hash: Option<String>,
+    pub migration_data: Option<String>,
+    pub migrated_at: u64,
 }
 
 /// Upgrade status enum
@@ -72,11 +128,13 @@ pub enum UpgradeStatus {
     Completed,    // Upgrade completed successfully
     Failed,       // Upgrade failed
     Cancelled,    // Upgrade cancelled
+    RolledBack,   // Upgrade completed, then undone by the issuer
+    Expired,      // Upgrade was not executed before its expiry time
 }
 
 /// Version compatibility matrix
 #[contracttype]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CompatibilityMatrix {
     pub version: CertificateVersion,
     pub compatible_versions: Vec<CertificateVersion>,
@@ -110,6 +168,9 @@ pub use crl::{
     CertificateRevocationList,
     Pagination,
     PaginatedResult,
+    CRLQueryFilter,
+    CRLCursor,
+    CRLQueryResult,
     VerificationResult,
 };
 
@@ -133,6 +194,7 @@ pub struct Certificate {
     pub issuer: Address,
     pub owner: Address,
     pub metadata_uri: String,
+    pub metadata_hash: Option<Bytes>,  // 32-byte SHA-256 of the metadata, set by upgrades
     pub issued_at: u64,
     pub revoked: bool,
     pub revocation_reason: Option<String>,
@@ -145,9 +207,20 @@ pub struct Certificate {
     pub is_upgradable: bool,                   // Whether this certificate can be upgraded
     pub upgrade_rules: Vec<UpgradeRule>,       // Valid upgrade paths
     pub compatibility_matrix: CompatibilityMatrix, // Version compatibility info
+    pub superseded: bool,                      // Replaced by another version in its lineage
     // Freeze-related fields
     pub frozen: bool,                          // Whether the certificate is frozen
-    pub freeze_info: Option<FrozenCertificateInfo>, // Freeze details
+    // Transfer-related fields
+    pub transfer_policy: TransferPolicy,       // Whether and how ownership may change
+}
+
+/// Issuer-defined transferability of a certificate
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TransferPolicy {
+    Free,                   // Owner may transfer without issuer involvement
+    RequiresIssuerApproval, // Issuer must co-sign each transfer before completion
+    NonTransferable,        // Soulbound: ownership can never change
 }
 
 /// Transfer status enum
@@ -159,6 +232,7 @@ pub enum TransferStatus {
     Rejected,     // Transfer rejected by recipient
     Cancelled,    // Transfer cancelled by sender
     Completed,    // Transfer completed successfully
+    Expired,      // Transfer lapsed before it was completed
 }
 
 /// Transfer request structure
@@ -172,12 +246,32 @@ pub struct TransferRequest {
     pub initiated_at: u64,       // When transfer was initiated
     pub accepted_at: Option<u64>, // When transfer was accepted
     pub completed_at: Option<u64>, // When transfer was completed
+    pub expires_at: u64,         // Transfer must complete before this time
+    pub issuer_approved_by: Option<Address>, // Issuer co-signature (RequiresIssuerApproval)
+    pub issuer_approved_at: Option<u64>,
     pub status: TransferStatus,   // Current status
     pub require_revocation: bool, // Whether to revoke on transfer
     pub transfer_fee: u64,        // Transfer fee (0 for no fee)
+    pub fee_token: Option<Address>, // Token the fee is escrowed in (None for no fee)
     pub memo: Option<String>,     // Optional memo for transfer
 }
 
+/// Who receives an escrowed transfer fee once the transfer completes
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TransferFeePolicy {
+    PayIssuer,    // Fee is paid out to the certificate issuer
+    RefundSender, // Fee acts as a deposit and is returned to the sender
+}
+
+/// Transfer fee configuration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferFeeConfig {
+    pub token: Address,
+    pub policy: TransferFeePolicy,
+}
+
 /// Transfer history entry
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -189,6 +283,8 @@ pub struct TransferHistory {
     pub transferred_at: u64,
     pub transfer_fee: u64,
     pub memo: Option<String>,
+    pub issuer_approved_by: Option<Address>, // Issuer that co-signed the transfer, if required
+    pub issuer_approved_at: Option<u64>,
 }
 
 /// Events for certificate transfers
@@ -210,6 +306,15 @@ pub struct TransferAcceptedEvent {
     pub accepted_at: u64,
 }
 
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TransferIssuerApprovedEvent {
+    pub transfer_id: String,
+    pub certificate_id: String,
+    pub issuer: Address,
+    pub approved_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct TransferCompletedEvent {
@@ -221,6 +326,16 @@ pub struct TransferCompletedEvent {
     pub transfer_fee: u64,
 }
 
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TransferFeeReleasedEvent {
+    pub transfer_id: String,
+    pub token: Address,
+    pub recipient: Address,
+    pub amount: i128,
+    pub refunded: bool,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct TransferRejectedEvent {
@@ -235,6 +350,13 @@ pub struct TransferCancelledEvent {
     pub cancelled_at: u64,
 }
 
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TransferExpiredEvent {
+    pub transfer_id: String,
+    pub expired_at: u64,
+}
+
 /// Upgrade events
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -277,6 +399,60 @@ pub struct UpgradeCompletedEvent {
     pub new_certificate_id: String,
 }
 
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct UpgradeStartedEvent {
+    pub upgrade_id: String,
+    pub started_by: Address,
+    pub started_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct UpgradeFailedEvent {
+    pub upgrade_id: String,
+    pub error_code: u32,
+    pub failed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct UpgradeCancelledEvent {
+    pub upgrade_id: String,
+    pub cancelled_by: Address,
+    pub cancelled_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct UpgradeExpiredEvent {
+    pub upgrade_id: String,
+    pub expired_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct UpgradeRolledBackEvent {
+    pub upgrade_id: String,
+    pub certificate_id: String,             // Restored parent certificate
+    pub rolled_back_certificate_id: String, // Superseded child certificate
+    pub from_version: CertificateVersion,
+    pub to_version: CertificateVersion,
+    pub rolled_back_by: Address,
+    pub rolled_back_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct MetadataMigratedEvent {
+    pub upgrade_id: String,
+    pub certificate_id: String,
+    pub old_metadata_uri: String,
+    pub new_metadata_uri: String,
+    pub new_metadata_hash: Option<Bytes>,
+    pub migration_script_hash: Option<String>,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct CertificateArchivedEvent {
@@ -289,7 +465,7 @@ pub struct CertificateArchivedEvent {
 
 /// Freeze information for a certificate
 #[contracttype]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FrozenCertificateInfo {
     pub certificate_id: String,
     pub frozen_at: u64,                    // Timestamp when the certificate was frozen
@@ -345,36 +521,52 @@ pub struct CertificateUnfrozenEvent {
 }
 
 /// Error types for the contract
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
 pub enum CertificateError {
-    AlreadyExists,
-    NotFound,
-    Unauthorized,
-    InvalidData,
-    AlreadyRevoked,
-    TransferNotFound,
-    TransferNotPending,
-    TransferNotAuthorized,
-    InsufficientBalance,
-    InvalidTransferStatus,
+    AlreadyExists = 1,
+    NotFound = 2,
+    Unauthorized = 3,
+    InvalidData = 4,
+    AlreadyRevoked = 5,
+    TransferNotFound = 6,
+    TransferNotPending = 7,
+    TransferNotAuthorized = 8,
+    InsufficientBalance = 9,
+    InvalidTransferStatus = 10,
+    TransferExpired = 28,
+    TransferLocked = 29,
+    TransferNotAllowed = 30,
+    IssuerApprovalRequired = 31,
     // Upgrade errors
-    UpgradeNotAllowed,
-    UpgradePathInvalid,
-    UpgradeNotApproved,
-    UpgradeAlreadyExists,
-    VersionConflict,
-    InvalidVersionFormat,
-    IncompatibleVersions,
-    CertificateNotUpgradable,
-    UpgradeInProgress,
-    ParentVersionNotFound,
+    UpgradeNotAllowed = 11,
+    UpgradePathInvalid = 12,
+    UpgradeNotApproved = 13,
+    UpgradeAlreadyExists = 14,
+    VersionConflict = 15,
+    InvalidVersionFormat = 16,
+    IncompatibleVersions = 17,
+    CertificateNotUpgradable = 18,
+    UpgradeInProgress = 19,
+    ParentVersionNotFound = 20,
+    UpgradeExpired = 34,
+    UpgradeNotOpen = 35,
     // Freeze errors
-    AlreadyFrozen,
-    NotFrozen,
-    FreezeDurationExceeded,
-    FreezeDurationInvalid,
-    FreezeNotExpired,
+    AlreadyFrozen = 21,
+    NotFrozen = 22,
+    FreezeDurationExceeded = 23,
+    FreezeDurationInvalid = 24,
+    FreezeNotExpired = 25,
+    // Fee errors
+    FeeNotConfigured = 26,
+    InvalidFee = 27,
+    // Batch root errors
+    IssuerNotAuthorized = 32,
+    RootAlreadyAnchored = 33,
+    FeeOverflow = 36,
+    // CRL errors
+    IssuerHasNoCrl = 37,
 }
 
 /// Storage keys for the contract
@@ -385,10 +577,14 @@ pub enum DataKey {
     TransferRequest(String),  // Transfer ID -> TransferRequest
     TransferHistory(String),  // Certificate ID -> Vec<TransferHistory>
     PendingTransfers(Address), // Address -> Vec<TransferID> (transfers pending acceptance)
+    AcceptedTransfers(Address), // Address -> Vec<TransferID> (accepted, awaiting completion)
+    ActiveTransfer(String),   // Certificate ID -> Transfer ID (lock while a transfer is open)
+    IssuerTransferPolicy(Address), // Issuer -> default TransferPolicy for new certificates
     TransferCount,            // Total number of transfers
     // Upgrade-related storage
     UpgradeRequest(String),   // Upgrade ID -> UpgradeRequest
     UpgradeHistory(String),   // Certificate ID -> Vec<UpgradeRequest>
+    MetadataMigration(String), // Upgraded certificate ID -> MetadataMigration
     ArchivedCertificate(String, CertificateVersion), // Certificate ID + Version -> ArchivedCertificate
     VersionChain(String),     // Certificate ID -> Vec<CertificateVersion> (version history)
     CompatibilityMatrix(CertificateVersion), // Version -> CompatibilityMatrix
@@ -398,6 +594,15 @@ pub enum DataKey {
     // Freeze-related storage
     FrozenCertificate(String), // Certificate ID -> FrozenCertificateInfo
     FreezeHistory(String),    // Certificate ID -> Vec<FreezeEvent>
+    // Administration and fees
+    Admin,                    // Contract administrator
+    FeeConfig,                // Verification fee configuration
+    TransferFeeConfig,        // Transfer fee token and release policy
+    CrlContract,              // CRLContract that mirrors revocations
+    // Batch Merkle roots
+    AuthorizedIssuer(Address), // Issuer -> bool (may anchor batch roots)
+    BatchRoot(BytesN<32>),    // Root -> AnchoredBatchRoot
+    BatchRevocations(BytesN<32>, u32), // (Root, word index) -> u128 revocation bitmap word
 }
 
 #[contracttype]
@@ -406,6 +611,8 @@ pub struct SingleVerificationResult {
     pub id: String,
     pub exists: bool,
     pub revoked: bool,
+    pub is_latest: bool,            // False when a newer version supersedes this certificate
+    pub latest_id: Option<String>,  // Current head of the lineage when not latest
     pub message: String,
 }
 
@@ -419,11 +626,87 @@ pub struct BatchVerificationResult {
     pub total_cost: u64,
 }
 
+/// Machine-readable status code for a single owner-bound verification
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum VerificationStatus {
+    Valid = 0,         // Certificate exists, is active and belongs to the expected owner
+    NotFound = 1,      // No certificate with this ID
+    Revoked = 2,       // Certificate has been revoked
+    Frozen = 3,        // Certificate is frozen pending a dispute
+    OwnerMismatch = 4, // Certificate belongs to a different address
+    InvalidProof = 5,  // Merkle proof or batch root could not be verified
+    OnHold = 6,        // Certificate is on hold in the CRL and may be released
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnerVerificationResult {
+    pub id: String,
+    pub status: VerificationStatus,
+    pub exists: bool,
+    pub revoked: bool,
+    pub frozen: bool,
+    pub owner_matches: bool,
+    pub issuer: Option<Address>,
+    pub version: CertificateVersion, // 0.0.0 when the certificate does not exist
+    pub is_latest: bool,             // False when a newer version supersedes this certificate
+    pub latest_id: Option<String>,   // Current head of the lineage when not latest
+    pub message: String,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnerBatchVerificationResult {
+    pub expected_owner: Address,
+    pub results: Vec<OwnerVerificationResult>,
+    pub total: u32,
+    pub successful: u32,
+    pub failed: u32,
+    pub total_cost: u64,
+}
+
+/// Verification fee configuration, priced in units of `token`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub token: Address,            // Soroban token contract used for payment
+    pub collector: Address,        // Receives the collected fees
+    pub base_fee: i128,            // Flat fee charged per paid batch
+    pub fee_per_certificate: i128, // Additional fee per verified certificate
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaidBatchVerificationResult {
+    pub verification: BatchVerificationResult,
+    pub fee_token: Address,
+    pub fee_paid: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct VerificationFeePaidEvent {
+    pub verifier: Address,
+    pub token: Address,
+    pub collector: Address,
+    pub amount: i128,
+    pub certificate_count: u32,
+    pub paid_at: u64,
+}
+
+/// Inclusion proof for a batch Merkle tree
+///
+/// `siblings` are ordered from the leaf level upwards. Bit `i` of `directions`
+/// is set when `siblings[i]` is the left-hand node, i.e. the running hash is a
+/// right child at that level.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct MerkleProof {
     pub leaf: BytesN<32>,
     pub siblings: Vec<BytesN<32>>,
+    pub directions: u32,
 }
 
 #[contracttype]
@@ -431,24 +714,100 @@ pub struct MerkleProof {
 pub struct MerkleVerificationResult {
     pub leaf: BytesN<32>,
     pub is_valid: bool,
+    pub issuer: Option<Address>,     // Issuer that anchored the root
+    pub batch_label: Option<String>, // Batch the root was anchored for
+}
+
+/// Canonical encoding of a certificate issued as a batch Merkle leaf
+///
+/// The leaf value committed to the tree is `sha256(leaf.to_xdr())`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchCertificateLeaf {
+    pub leaf_index: u32, // Position in the batch, used for per-leaf revocation
+    pub id: String,
+    pub owner: Address,
+    pub metadata_uri: String,
+    pub issued_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchCertificateVerification {
+    pub status: VerificationStatus,
+    pub is_valid: bool,
+    pub root: BytesN<32>,
+    pub issuer: Option<Address>,     // Issuer that anchored the root
+    pub batch_label: Option<String>,
+    pub leaf_index: u32,
+    pub id: String,
+    pub owner: Address,
+    pub metadata_uri: String,
+    pub issued_at: u64,
+    pub leaf_revoked: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BatchLeafRevokedEvent {
+    pub root: BytesN<32>,
+    pub leaf_index: u32,
+    pub revoked_by: Address,
+    pub revoked_at: u64,
+}
+
+/// Batch Merkle root anchored on-chain by an issuer
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AnchoredBatchRoot {
+    pub root: BytesN<32>,
+    pub issuer: Address,
+    pub batch_label: String,
+    pub leaf_count: u32,
+    pub anchored_at: u64,
+    pub revoked: bool,
+    pub revoked_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BatchRootAnchoredEvent {
+    pub root: BytesN<32>,
+    pub issuer: Address,
+    pub batch_label: String,
+    pub leaf_count: u32,
+    pub anchored_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BatchRootRevokedEvent {
+    pub root: BytesN<32>,
+    pub issuer: Address,
+    pub revoked_at: u64,
 }
 
 #[contract]
 pub struct CertificateContract;
 
 impl CertificateVersion {
-    /// Compare two versions
+    /// Compare two versions by semantic-versioning precedence
+    ///
+    /// Returns -1, 0 or 1. Pre-release versions rank below the release they
+    /// precede, pre-release identifiers are compared field by field, and build
+    /// metadata is ignored.
     pub fn compare(&self, other: &CertificateVersion) -> i32 {
-        if self.major != other.major {
-            return self.major as i32 - other.major as i32;
-        }
-        if self.minor != other.minor {
-            return self.minor as i32 - other.minor as i32;
-        }
-        if self.patch != other.patch {
-            return self.patch as i32 - other.patch as i32;
+        let ordering = self
+            .major
+            .cmp(&other.major)
+            .then(self.minor.cmp(&other.minor))
+            .then(self.patch.cmp(&other.patch))
+            .then_with(|| compare_pre_release(&self.pre_release, &other.pre_release));
+        match ordering {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
         }
-        0 // Versions are equal
     }
     
     /// Check if this version is greater than another
@@ -466,106 +825,968 @@ impl CertificateVersion {
         self.compare(other) == 0
     }
     
-    /// Get version as string (e.g., "1.2.3")
-    pub fn to_string(&self, env: &Env) -> String {
-        let mut version_str = String::from_str(env, &format!("{}.{}.", self.major, self.minor));
-        version_str.push_str(&self.patch.to_string());
+    /// Check that the pre-release and build labels are well-formed
+    ///
+    /// Labels longer than `MAX_VERSION_LABEL_LENGTH` bytes are rejected here,
+    /// so every validated version can be formatted in full.
+    pub fn validate(&self) -> Result<(), CertificateError> {
+        let mut buf = [0u8; MAX_VERSION_LABEL_LENGTH];
+        if let Some(pre_release) = &self.pre_release {
+            let label =
+                copy_label(pre_release, &mut buf).ok_or(CertificateError::InvalidVersionFormat)?;
+            if !is_valid_label(label, true) {
+                return Err(CertificateError::InvalidVersionFormat);
+            }
+        }
         if let Some(build) = &self.build {
-            version_str.push_str(&format!("-{}", build));
+            let label =
+                copy_label(build, &mut buf).ok_or(CertificateError::InvalidVersionFormat)?;
+            if !is_valid_label(label, false) {
+                return Err(CertificateError::InvalidVersionFormat);
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a version string such as "2.0.0-beta.1+exp.sha.5114f85"
+    pub fn parse(env: &Env, version: &String) -> Result<CertificateVersion, CertificateError> {
+        let len = version.len() as usize;
+        if len == 0 || len > MAX_VERSION_STRING_LENGTH {
+            return Err(CertificateError::InvalidVersionFormat);
+        }
+        let mut buf = [0u8; MAX_VERSION_STRING_LENGTH];
+        version.copy_into_slice(&mut buf[..len]);
+        let text = &buf[..len];
+
+        // Split off "+build", then "-pre-release"; the core has no '-' or '+'
+        let (rest, build) = match text.iter().position(|b| *b == b'+') {
+            Some(i) => (&text[..i], Some(&text[i + 1..])),
+            None => (text, None),
+        };
+        let (core, pre_release) = match rest.iter().position(|b| *b == b'-') {
+            Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+            None => (rest, None),
+        };
+
+        let mut numbers = [0u32; 3];
+        let mut count = 0;
+        for part in core.split(|b| *b == b'.') {
+            if count == 3 {
+                return Err(CertificateError::InvalidVersionFormat);
+            }
+            numbers[count] = parse_numeric(part).ok_or(CertificateError::InvalidVersionFormat)?;
+            count += 1;
+        }
+        if count != 3 {
+            return Err(CertificateError::InvalidVersionFormat);
+        }
+
+        let parsed = CertificateVersion {
+            major: numbers[0],
+            minor: numbers[1],
+            patch: numbers[2],
+            pre_release: pre_release.map(|label| String::from_bytes(env, label)),
+            build: build.map(|label| String::from_bytes(env, label)),
+        };
+        parsed.validate()?;
+        Ok(parsed)
+    }
+
+    /// Format as a version string (e.g., "1.2.3-rc.1+build.7")
+    ///
+    /// The version must have passed `validate`; over-long labels panic.
+    pub fn format(&self, env: &Env) -> String {
+        let mut buf = [0u8; MAX_VERSION_STRING_LENGTH];
+        let len = self.write_into(&mut buf);
+        String::from_bytes(env, &buf[..len])
+    }
+
+    /// Write the formatted version into `buf`, returning the number of bytes written
+    fn write_into(&self, buf: &mut [u8; MAX_VERSION_STRING_LENGTH]) -> usize {
+        let mut pos = write_u32(buf, 0, self.major);
+        buf[pos] = b'.';
+        pos = write_u32(buf, pos + 1, self.minor);
+        buf[pos] = b'.';
+        pos = write_u32(buf, pos + 1, self.patch);
+        for (separator, label) in [(b'-', &self.pre_release), (b'+', &self.build)] {
+            if let Some(label) = label {
+                let len = label.len() as usize;
+                buf[pos] = separator;
+                label.copy_into_slice(&mut buf[pos + 1..pos + 1 + len]);
+                pos += 1 + len;
+            }
         }
-        version_str
+        pos
     }
 }
 
-impl CertificateContract {
-    /// Validate upgrade path
-    fn validate_upgrade_path(
-        env: &Env,
-        from_version: &CertificateVersion,
-        to_version: &CertificateVersion,
-        upgrade_rules: &Vec<UpgradeRule>,
-    ) -> Result<Option<UpgradeRule>, CertificateError> {
-        // Check if it's actually an upgrade (to_version > from_version)
-        if !to_version.is_greater_than(from_version) {
-            return Err(CertificateError::VersionConflict);
+/// Copy a version label into `buf`, or `None` if it is empty or too long
+fn copy_label<'a>(label: &String, buf: &'a mut [u8; MAX_VERSION_LABEL_LENGTH]) -> Option<&'a [u8]> {
+    let len = label.len() as usize;
+    if len == 0 || len > MAX_VERSION_LABEL_LENGTH {
+        return None;
+    }
+    label.copy_into_slice(&mut buf[..len]);
+    Some(&buf[..len])
+}
+
+/// Dot-separated, non-empty `[0-9A-Za-z-]` identifiers; pre-release numerics have no leading zeros
+fn is_valid_label(label: &[u8], is_pre_release: bool) -> bool {
+    label.split(|b| *b == b'.').all(|identifier| {
+        !identifier.is_empty()
+            && identifier
+                .iter()
+                .all(|b| b.is_ascii_alphanumeric() || *b == b'-')
+            && !(is_pre_release
+                && identifier.len() > 1
+                && identifier[0] == b'0'
+                && identifier.iter().all(|b| b.is_ascii_digit()))
+    })
+}
+
+/// Parse a semver numeric field: digits only, no leading zeros, fits in u32
+fn parse_numeric(digits: &[u8]) -> Option<u32> {
+    if digits.is_empty() || (digits.len() > 1 && digits[0] == b'0') {
+        return None;
+    }
+    let mut value: u32 = 0;
+    for b in digits {
+        if !b.is_ascii_digit() {
+            return None;
         }
-        
-        // Check specific upgrade rules
-        for rule in upgrade_rules.iter() {
-            if rule.from_version.is_equal(from_version) && rule.to_version.is_equal(to_version) {
-                if !rule.allowed {
-                    return Err(CertificateError::UpgradeNotAllowed);
+        value = value.checked_mul(10)?.checked_add((b - b'0') as u32)?;
+    }
+    Some(value)
+}
+
+/// Semver precedence of two optional pre-release labels
+fn compare_pre_release(a: &Option<String>, b: &Option<String>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        // A release outranks any of its pre-releases
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            let mut buf_a = [0u8; MAX_VERSION_LABEL_LENGTH];
+            let mut buf_b = [0u8; MAX_VERSION_LABEL_LENGTH];
+            match (copy_label(a, &mut buf_a), copy_label(b, &mut buf_b)) {
+                (Some(a), Some(b)) => compare_identifiers(a, b),
+                // Labels that fail validation still get a deterministic order
+                _ => a.cmp(b),
+            }
+        }
+    }
+}
+
+/// Compare dot-separated pre-release identifiers field by field
+fn compare_identifiers(a: &[u8], b: &[u8]) -> Ordering {
+    let mut left = a.split(|c| *c == b'.');
+    let mut right = b.split(|c| *c == b'.');
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return Ordering::Equal,
+            // A larger set of fields has higher precedence when all others are equal
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let x_numeric = !x.is_empty() && x.iter().all(|c| c.is_ascii_digit());
+                let y_numeric = !y.is_empty() && y.iter().all(|c| c.is_ascii_digit());
+                let ordering = match (x_numeric, y_numeric) {
+                    // Numeric identifiers compare numerically (no leading zeros, so by length first)
+                    (true, true) => x.len().cmp(&y.len()).then(x.cmp(y)),
+                    // Numeric identifiers rank below alphanumeric ones
+                    (true, false) => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (false, false) => x.cmp(y),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
                 }
-                return Ok(Some(rule.clone()));
             }
         }
-        
-        // Default validation: major version changes require explicit rules
-        if to_version.major > from_version.major {
-            return Err(CertificateError::UpgradePathInvalid);
+    }
+}
+
+/// Write the decimal digits of `value` at `pos`, returning the position after them
+fn write_u32(buf: &mut [u8], pos: usize, value: u32) -> usize {
+    let mut digits = [0u8; 10];
+    let mut n = value;
+    let mut count = 0;
+    loop {
+        digits[count] = b'0' + (n % 10) as u8;
+        count += 1;
+        n /= 10;
+        if n == 0 {
+            break;
         }
-        
-        // Minor and patch upgrades are allowed by default
-        Ok(None)
     }
-    
-    /// Check version compatibility
-    fn check_compatibility(
-        env: &Env,
-        version: &CertificateVersion,
-        target_version: &CertificateVersion,
-    ) -> Result<bool, CertificateError> {
-        let compatibility_key = DataKey::CompatibilityMatrix(version.clone());
-        if let Some(matrix) = env.storage().instance().get::<DataKey, CompatibilityMatrix>(&compatibility_key) {
-            // Check if target version is in compatible list
-            for compatible_version in matrix.compatible_versions.iter() {
-                if compatible_version.is_equal(target_version) {
-                    return Ok(true);
-                }
+    for i in 0..count {
+        buf[pos + i] = digits[count - 1 - i];
+    }
+    pos + count
+}
+
+impl CertificateContract {
+    /// Look up an anchored root and whether proofs against it should be trusted
+    fn trusted_batch_root(env: &Env, root: &BytesN<32>) -> (Option<AnchoredBatchRoot>, bool) {
+        let anchored: Option<AnchoredBatchRoot> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BatchRoot(root.clone()));
+        let trusted = match &anchored {
+            Some(batch) => !batch.revoked && Self::is_issuer_authorized(env, &batch.issuer),
+            None => false,
+        };
+        (anchored, trusted)
+    }
+
+    /// Write a batch entry to persistent storage and extend its TTL
+    fn persist_batch_entry<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage()
+            .persistent()
+            .extend_ttl(key, BATCH_TTL_THRESHOLD, BATCH_TTL_EXTEND_TO);
+    }
+
+    /// Direction bits and depth a proof for `leaf_index` must have in a tree of
+    /// `leaf_count` leaves, following the promote-odd-node construction
+    fn batch_leaf_path(leaf_index: u32, leaf_count: u32) -> (u32, u32) {
+        let (mut index, mut width) = (leaf_index, leaf_count);
+        let (mut directions, mut depth) = (0u32, 0u32);
+        while width > 1 {
+            // A promoted last node contributes neither a sibling nor a bit
+            if !(index == width - 1 && width % 2 == 1) {
+                directions |= (index & 1) << depth;
+                depth += 1;
             }
-            return Ok(false);
+            index /= 2;
+            width = width.div_ceil(2);
         }
-        
-        // Default compatibility: same major version is compatible
-        Ok(version.major == target_version.major)
+        (directions, depth)
     }
-    
-    /// Archive a certificate version
-    fn archive_certificate_version(
-        env: &mut Env,
-        certificate_id: String,
-        version: CertificateVersion,
-        archiver: Address,
-        reason: String,
-    ) -> Result<(), CertificateError> {
-        // Get the certificate to archive
-        let cert_key = DataKey::Certificate(certificate_id.clone());
-        let certificate: Certificate = env
+
+    /// Storage key and bit mask for a leaf in a root's revocation bitmap
+    fn batch_revocation_slot(root: &BytesN<32>, leaf_index: u32) -> (DataKey, u128) {
+        (
+            DataKey::BatchRevocations(root.clone(), leaf_index / 128),
+            1u128 << (leaf_index % 128),
+        )
+    }
+
+    /// Load the contract admin and require its authorization
+    fn require_admin(env: &Env) {
+        let admin: Address = env
             .storage()
             .instance()
-            .get(&cert_key)
-            .ok_or(CertificateError::NotFound)?;
-        
-        // Verify version matches
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        admin.require_auth();
+    }
+
+    /// Whether an issuer is currently allowed to anchor batch roots
+    fn is_issuer_authorized(env: &Env, issuer: &Address) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::AuthorizedIssuer(issuer.clone()))
+            .unwrap_or(false)
+    }
+
+    /// Whether an open transfer has passed its expiry time
+    fn is_transfer_expired(env: &Env, transfer: &TransferRequest) -> bool {
+        (transfer.status == TransferStatus::Pending || transfer.status == TransferStatus::Accepted)
+            && env.ledger().timestamp() > transfer.expires_at
+    }
+
+    /// Remove a transfer from one of the recipient's transfer lists
+    fn remove_listed_transfer(env: &Env, list_key: &DataKey, transfer_id: &String) {
+        let listed: Vec<String> = env
+            .storage()
+            .instance()
+            .get(list_key)
+            .unwrap_or(Vec::new(env));
+
+        let mut remaining = Vec::new(env);
+        for listed_id in listed.iter() {
+            if listed_id != *transfer_id {
+                remaining.push_back(listed_id);
+            }
+        }
+        env.storage().instance().set(list_key, &remaining);
+    }
+
+    /// Clean up after a transfer reaches a terminal state
+    fn close_transfer(env: &Env, transfer: &TransferRequest) {
+        let list_key = match transfer.accepted_at {
+            Some(_) => DataKey::AcceptedTransfers(transfer.to_address.clone()),
+            None => DataKey::PendingTransfers(transfer.to_address.clone()),
+        };
+        Self::remove_listed_transfer(env, &list_key, &transfer.id);
+        env.storage()
+            .instance()
+            .remove(&DataKey::ActiveTransfer(transfer.certificate_id.clone()));
+    }
+
+    /// Mark an open transfer as expired, refund its fee and release the lock
+    fn expire_open_transfer(env: &Env, transfer: &mut TransferRequest) {
+        transfer.status = TransferStatus::Expired;
+        env.storage()
+            .instance()
+            .set(&DataKey::TransferRequest(transfer.id.clone()), transfer);
+
+        Self::release_transfer_fee(env, transfer, &transfer.from_address, true);
+        Self::close_transfer(env, transfer);
+
+        env.events().publish(
+            (symbol_short!("xfer_exp"),),
+            TransferExpiredEvent {
+                transfer_id: transfer.id.clone(),
+                expired_at: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    /// Move the transfer fee from the sender into contract escrow
+    fn escrow_transfer_fee(
+        env: &Env,
+        from_address: &Address,
+        transfer_fee: u64,
+    ) -> Result<Option<Address>, CertificateError> {
+        if transfer_fee == 0 {
+            return Ok(None);
+        }
+
+        let config: TransferFeeConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::TransferFeeConfig)
+            .ok_or(CertificateError::FeeNotConfigured)?;
+
+        let amount = transfer_fee as i128;
+        let token_client = token::Client::new(env, &config.token);
+        if token_client.balance(from_address) < amount {
+            return Err(CertificateError::InsufficientBalance);
+        }
+        token_client.transfer(from_address, &env.current_contract_address(), &amount);
+
+        Ok(Some(config.token))
+    }
+
+    /// Pay an escrowed transfer fee out of the contract
+    fn release_transfer_fee(
+        env: &Env,
+        transfer: &TransferRequest,
+        recipient: &Address,
+        refunded: bool,
+    ) {
+        let token_address = match &transfer.fee_token {
+            Some(token_address) => token_address.clone(),
+            None => return,
+        };
+
+        let amount = transfer.transfer_fee as i128;
+        token::Client::new(env, &token_address).transfer(
+            &env.current_contract_address(),
+            recipient,
+            &amount,
+        );
+
+        env.events().publish(
+            (symbol_short!("fee_rel"),),
+            TransferFeeReleasedEvent {
+                transfer_id: transfer.id.clone(),
+                token: token_address,
+                recipient: recipient.clone(),
+                amount,
+                refunded,
+            },
+        );
+    }
+
+    /// Cost of verifying a non-empty batch of `count` certificates
+    fn batch_verification_cost(count: u32) -> u64 {
+        BASE_VERIFICATION_COST + (COST_PER_CERTIFICATE * (count as u64))
+    }
+
+    /// Verify one certificate against the address presenting it
+    fn verify_for_owner(
+        env: &Env,
+        id: String,
+        expected_owner: &Address,
+    ) -> OwnerVerificationResult {
+        let cert: Certificate = match env.storage().instance().get(&id) {
+            Some(cert) => cert,
+            None => {
+                return OwnerVerificationResult {
+                    id,
+                    status: VerificationStatus::NotFound,
+                    exists: false,
+                    revoked: false,
+                    frozen: false,
+                    owner_matches: false,
+                    issuer: None,
+                    version: CertificateVersion {
+                        major: 0,
+                        minor: 0,
+                        patch: 0,
+                        pre_release: None,
+                        build: None,
+                    },
+                    is_latest: false,
+                    latest_id: None,
+                    message: String::from_str(env, "Certificate not found"),
+                };
+            }
+        };
+
+        let owner_matches = cert.owner == *expected_owner;
+        let latest_id = Self::superseding_certificate_id(env, &cert);
+
+        let revoked = Self::is_effectively_revoked(env, &cert);
+        let frozen = Self::is_effectively_frozen(env, &cert);
+
+        // Revocation outranks a freeze, which outranks an ownership mismatch
+        let (status, message) = if revoked && !cert.revoked && Self::is_on_hold_in_crl(env, &cert) {
+            (VerificationStatus::OnHold, "Certificate is on hold")
+        } else if revoked {
+            (VerificationStatus::Revoked, "Certificate is revoked")
+        } else if frozen {
+            (VerificationStatus::Frozen, "Certificate is frozen")
+        } else if !owner_matches {
+            (
+                VerificationStatus::OwnerMismatch,
+                "Certificate belongs to a different owner",
+            )
+        } else {
+            (VerificationStatus::Valid, "Certificate is valid")
+        };
+
+        OwnerVerificationResult {
+            id,
+            status,
+            exists: true,
+            revoked,
+            frozen,
+            owner_matches,
+            issuer: Some(cert.issuer),
+            version: cert.version,
+            is_latest: latest_id.is_none(),
+            latest_id,
+            message: String::from_str(env, message),
+        }
+    }
+
+    /// Walk a certificate's lineage from its original version to the current head
+    ///
+    /// Follows parent links to the root and then child links forward, so
+    /// rolled-back versions (which the chain no longer points to) are skipped.
+    fn lineage_of(env: &Env, cert: Certificate) -> Vec<Certificate> {
+        let mut root = cert;
+        let mut steps = 0;
+        while let Some(parent_id) = root.parent_certificate_id.clone() {
+            if steps >= MAX_LINEAGE_LENGTH {
+                break;
+            }
+            match env
+                .storage()
+                .instance()
+                .get::<String, Certificate>(&parent_id)
+            {
+                Some(parent) => root = parent,
+                None => break,
+            }
+            steps += 1;
+        }
+
+        let mut lineage = Vec::new(env);
+        let mut next = Some(root);
+        while let Some(current) = next {
+            if lineage.len() >= MAX_LINEAGE_LENGTH {
+                break;
+            }
+            next = match current.child_certificate_id.clone() {
+                Some(child_id) => env.storage().instance().get(&child_id),
+                None => None,
+            };
+            lineage.push_back(current);
+        }
+        lineage
+    }
+
+    /// Map a free-form revocation reason onto the CRL's RevocationReason
+    ///
+    /// Reasons spelled like a permanent variant map to it; anything else,
+    /// including holds, becomes `Other` since revocations here are final.
+    fn map_revocation_reason(env: &Env, reason: &String) -> RevocationReason {
+        let known = [
+            ("KeyCompromise", RevocationReason::KeyCompromise),
+            ("CACompromise", RevocationReason::CACompromise),
+            ("AffiliationChanged", RevocationReason::AffiliationChanged),
+            ("Superseded", RevocationReason::Superseded),
+            (
+                "CessationOfOperation",
+                RevocationReason::CessationOfOperation,
+            ),
+            ("PrivilegeWithdrawn", RevocationReason::PrivilegeWithdrawn),
+            ("AACompromise", RevocationReason::AACompromise),
+        ];
+        for (name, mapped) in known {
+            if *reason == String::from_str(env, name) {
+                return mapped;
+            }
+        }
+        RevocationReason::Other(reason.clone())
+    }
+
+    /// Client for the configured CRL contract, if it holds a CRL for `issuer`
+    ///
+    /// Issuers the CRL contract has not registered have no CRL, which is the
+    /// only case treated as "not listed". Any other CRL failure is an error.
+    fn issuer_crl<'a>(env: &'a Env, issuer: &Address) -> Option<CRLContractClient<'a>> {
+        let crl_contract: Address = env.storage().instance().get(&DataKey::CrlContract)?;
+        let crl = CRLContractClient::new(env, &crl_contract);
+        // The CRL contract traps on lookups for issuers it has not registered
+        match crl.try_get_crl_metadata(issuer) {
+            Ok(Ok(_)) => Some(crl),
+            _ => None,
+        }
+    }
+
+    /// Mirror a revocation into the configured CRL contract
+    ///
+    /// The certificate contract signs as a delegated CRL signer of the issuer.
+    /// A certificate on hold in the CRL is made permanent; one already revoked
+    /// there is left alone. Without a configured CRL contract there is nothing
+    /// to mirror, but an issuer the CRL contract has not registered is an error,
+    /// so the two contracts never disagree about a revocation.
+    fn propagate_revocation(
+        env: &Env,
+        cert: &Certificate,
+        reason: &String,
+    ) -> Result<(), CertificateError> {
+        if !env.storage().instance().has(&DataKey::CrlContract) {
+            return Ok(());
+        }
+        let crl = Self::issuer_crl(env, &cert.issuer).ok_or(CertificateError::IssuerHasNoCrl)?;
+
+        if let Some(entry) = crl.get_revocation_info(&cert.issuer, &cert.id) {
+            if entry.reason != RevocationReason::CertificateHold {
+                return Ok(());
+            }
+        }
+        crl.revoke_certificate(
+            &cert.issuer,
+            &env.current_contract_address(),
+            &cert.id,
+            &Self::map_revocation_reason(env, reason),
+            &None,
+        );
+        Ok(())
+    }
+
+    /// Whether the configured CRL lists the certificate, on hold or revoked
+    ///
+    /// Issuers without a CRL in the CRL contract count as not listed. If the
+    /// lookup fails for an issuer that has one, the certificate counts as
+    /// listed, so guards fail closed.
+    fn is_listed_in_crl(env: &Env, cert: &Certificate) -> bool {
+        match Self::issuer_crl(env, &cert.issuer) {
+            Some(crl) => !matches!(crl.try_is_revoked(&cert.issuer, &cert.id), Ok(Ok(false))),
+            None => false,
+        }
+    }
+
+    /// Whether the CRL lists the certificate with the reversible `CertificateHold` reason
+    fn is_on_hold_in_crl(env: &Env, cert: &Certificate) -> bool {
+        let Some(crl) = Self::issuer_crl(env, &cert.issuer) else {
+            return false;
+        };
+        matches!(
+            crl.try_get_revocation_info(&cert.issuer, &cert.id),
+            Ok(Ok(Some(entry))) if entry.reason == RevocationReason::CertificateHold
+        )
+    }
+
+    /// Whether a freeze is still in force; timed freezes lapse at `unfreeze_at`
+    /// even before anyone calls `unfreeze_certificate`
+    fn is_effectively_frozen(env: &Env, cert: &Certificate) -> bool {
+        if !cert.frozen {
+            return false;
+        }
+        let freeze_info: Option<FrozenCertificateInfo> = env
+            .storage()
+            .instance()
+            .get(&DataKey::FrozenCertificate(cert.id.clone()));
+        match freeze_info.and_then(|info| info.unfreeze_at) {
+            Some(unfreeze_at) => env.ledger().timestamp() < unfreeze_at,
+            None => true,
+        }
+    }
+
+    /// Revocation as seen by verifiers: this contract's flag or the CRL
+    fn is_effectively_revoked(env: &Env, cert: &Certificate) -> bool {
+        cert.revoked || Self::is_listed_in_crl(env, cert)
+    }
+
+    /// ID of the version that currently supersedes `cert`, if any
+    fn superseding_certificate_id(env: &Env, cert: &Certificate) -> Option<String> {
+        if !cert.superseded {
+            return None;
+        }
+        let lineage = Self::lineage_of(env, cert.clone());
+        lineage
+            .last()
+            .map(|head| head.id)
+            .filter(|head_id| *head_id != cert.id)
+    }
+
+    /// Validate upgrade path
+    fn validate_upgrade_path(
+        env: &Env,
+        from_version: &CertificateVersion,
+        to_version: &CertificateVersion,
+        upgrade_rules: &Vec<UpgradeRule>,
+    ) -> Result<Option<UpgradeRule>, CertificateError> {
+        // Check if it's actually an upgrade (to_version > from_version)
+        if !to_version.is_greater_than(from_version) {
+            return Err(CertificateError::VersionConflict);
+        }
+        
+        // Certificate-specific rules take precedence over global ones
+        let global_rules: Vec<UpgradeRule> = env
+            .storage()
+            .instance()
+            .get(&DataKey::UpgradeRules)
+            .unwrap_or(Vec::new(env));
+        let matching_rule = Self::find_upgrade_rule(upgrade_rules, from_version, to_version)
+            .or_else(|| Self::find_upgrade_rule(&global_rules, from_version, to_version));
+        if let Some(rule) = matching_rule {
+            if !rule.allowed {
+                return Err(CertificateError::UpgradeNotAllowed);
+            }
+            return Ok(Some(rule));
+        }
+        
+        // Default validation: major version changes require explicit rules
+        if to_version.major > from_version.major {
+            return Err(CertificateError::UpgradePathInvalid);
+        }
+        
+        // Minor and patch upgrades are allowed by default
+        Ok(None)
+    }
+    
+    /// Find the rule for an exact upgrade path
+    fn find_upgrade_rule(
+        rules: &Vec<UpgradeRule>,
+        from_version: &CertificateVersion,
+        to_version: &CertificateVersion,
+    ) -> Option<UpgradeRule> {
+        rules.iter().find(|rule| {
+            rule.from_version.is_equal(from_version) && rule.to_version.is_equal(to_version)
+        })
+    }
+
+    /// Check version compatibility
+    fn check_compatibility(
+        env: &Env,
+        version: &CertificateVersion,
+        target_version: &CertificateVersion,
+    ) -> Result<bool, CertificateError> {
+        let compatibility_key = DataKey::CompatibilityMatrix(version.clone());
+        if let Some(matrix) = env.storage().instance().get::<DataKey, CompatibilityMatrix>(&compatibility_key) {
+            // Check if target version is in compatible list
+            for compatible_version in matrix.compatible_versions.iter() {
+                if compatible_version.is_equal(target_version) {
+                    return Ok(true);
+                }
+            }
+            return Ok(false);
+        }
+        
+        // Default compatibility: same major version is compatible
+        Ok(version.major == target_version.major)
+    }
+    
+    /// ID of the certificate produced by upgrading `certificate_id`, e.g. "cert-1_v1.1.0"
+    fn upgraded_certificate_id(
+        env: &Env,
+        certificate_id: &String,
+        version: &CertificateVersion,
+    ) -> Result<String, CertificateError> {
+        let id_len = certificate_id.len() as usize;
+        if id_len > MAX_CERTIFICATE_ID_LENGTH {
+            return Err(CertificateError::InvalidData);
+        }
+        version.validate()?;
+        let mut version_buf = [0u8; MAX_VERSION_STRING_LENGTH];
+        let version_len = version.write_into(&mut version_buf);
+
+        let mut buf = [0u8; MAX_CERTIFICATE_ID_LENGTH + 2 + MAX_VERSION_STRING_LENGTH];
+        certificate_id.copy_into_slice(&mut buf[..id_len]);
+        buf[id_len..id_len + 2].copy_from_slice(b"_v");
+        buf[id_len + 2..id_len + 2 + version_len].copy_from_slice(&version_buf[..version_len]);
+        Ok(String::from_bytes(env, &buf[..id_len + 2 + version_len]))
+    }
+
+    /// Whether an upgrade must wait for issuer approval
+    ///
+    /// A matching rule decides; without one, minor and patch upgrades are
+    /// auto-approved and major upgrades need the issuer.
+    fn upgrade_requires_approval(
+        from_version: &CertificateVersion,
+        to_version: &CertificateVersion,
+        rule: &Option<UpgradeRule>,
+    ) -> bool {
+        match rule {
+            Some(rule) => rule.requires_issuer_approval,
+            None => to_version.major > from_version.major,
+        }
+    }
+
+    /// Store an upgrade request and replace its entry in the certificate's history
+    fn save_upgrade_request(env: &Env, request: &UpgradeRequest) {
+        env.storage()
+            .instance()
+            .set(&DataKey::UpgradeRequest(request.id.clone()), request);
+
+        let history_key = DataKey::UpgradeHistory(request.certificate_id.clone());
+        let history: Vec<UpgradeRequest> = env
+            .storage()
+            .instance()
+            .get(&history_key)
+            .unwrap_or(Vec::new(env));
+        let mut updated = Vec::new(env);
+        for entry in history.iter() {
+            if entry.id == request.id {
+                updated.push_back(request.clone());
+            } else {
+                updated.push_back(entry);
+            }
+        }
+        env.storage().instance().set(&history_key, &updated);
+    }
+
+    /// Drop an upgrade from an issuer's pending approval list
+    fn remove_pending_upgrade(env: &Env, issuer: &Address, upgrade_id: &String) {
+        let pending_key = DataKey::PendingUpgrades(issuer.clone());
+        let pending_upgrades: Vec<String> = env
+            .storage()
+            .instance()
+            .get(&pending_key)
+            .unwrap_or(Vec::new(env));
+
+        let mut new_pending = Vec::new(env);
+        for pending_id in pending_upgrades.iter() {
+            if pending_id != *upgrade_id {
+                new_pending.push_back(pending_id);
+            }
+        }
+        env.storage().instance().set(&pending_key, &new_pending);
+    }
+
+    /// Whether an open upgrade request has passed its expiry time
+    fn is_upgrade_expired(env: &Env, request: &UpgradeRequest) -> bool {
+        (request.status == UpgradeStatus::Pending || request.status == UpgradeStatus::Approved)
+            && env.ledger().timestamp() > request.expires_at
+    }
+
+    /// Move an open upgrade request to a terminal status and drop it from the pending list
+    fn close_upgrade(env: &Env, request: &mut UpgradeRequest, status: UpgradeStatus) {
+        request.status = status;
+        Self::save_upgrade_request(env, request);
+
+        if let Some(certificate) = env
+            .storage()
+            .instance()
+            .get::<String, Certificate>(&request.certificate_id)
+        {
+            Self::remove_pending_upgrade(env, &certificate.issuer, &request.id);
+        }
+    }
+
+    /// Mark a lapsed upgrade request as expired
+    fn expire_open_upgrade(env: &Env, request: &mut UpgradeRequest) {
+        Self::close_upgrade(env, request, UpgradeStatus::Expired);
+
+        env.events().publish(
+            (symbol_short!("upg_exp"),),
+            UpgradeExpiredEvent {
+                upgrade_id: request.id.clone(),
+                expired_at: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    /// Archive the current version and create its upgraded successor
+    fn apply_upgrade(
+        env: &Env,
+        upgrade_id: &String,
+        upgrade_request: &mut UpgradeRequest,
+        mut certificate: Certificate,
+        executor: Address,
+    ) -> Result<String, CertificateError> {
+        // Create new certificate with upgraded version
+        let new_certificate_id =
+            Self::upgraded_certificate_id(env, &certificate.id, &upgrade_request.to_version)?;
+
+        // Never overwrite an existing certificate, e.g. a rolled-back version
+        if env.storage().instance().has(&new_certificate_id) {
+            return Err(CertificateError::VersionConflict);
+        }
+
+        // Archive the current version
+        Self::archive_certificate_version(
+            env,
+            certificate.id.clone(),
+            certificate.version.clone(),
+            executor.clone(),
+            String::from_str(env, "Upgraded to newer version"),
+        )?;
+
+        // Migrate metadata if the request carries a new document
+        let new_metadata_uri = upgrade_request
+            .new_metadata_uri
+            .clone()
+            .unwrap_or(certificate.metadata_uri.clone());
+        let new_metadata_hash = match &upgrade_request.new_metadata_hash {
+            Some(hash) => Some(hash.clone()),
+            None => certificate.metadata_hash.clone(),
+        };
+
+        let new_certificate = Certificate {
+            id: new_certificate_id.clone(),
+            issuer: certificate.issuer.clone(),
+            owner: certificate.owner.clone(),
+            metadata_uri: new_metadata_uri.clone(),
+            metadata_hash: new_metadata_hash.clone(),
+            issued_at: env.ledger().timestamp(),
+            revoked: false,
+            revocation_reason: None,
+            revoked_at: None,
+            revoked_by: None,
+            version: upgrade_request.to_version.clone(),
+            parent_certificate_id: Some(certificate.id.clone()),
+            child_certificate_id: None,
+            is_upgradable: certificate.is_upgradable,
+            upgrade_rules: certificate.upgrade_rules.clone(),
+            transfer_policy: certificate.transfer_policy.clone(),
+            compatibility_matrix: certificate.compatibility_matrix.clone(),
+            superseded: false,
+            frozen: false,
+        };
+
+        // Store new certificate
+        env.storage()
+            .instance()
+            .set(&new_certificate_id, &new_certificate);
+
+        // Update parent certificate's child reference
+        certificate.child_certificate_id = Some(new_certificate_id.clone());
+        certificate.superseded = true;
+        env.storage().instance().set(&certificate.id, &certificate);
+
+        // Record what changed between the two versions
+        let migration = MetadataMigration {
+            upgrade_id: upgrade_id.clone(),
+            from_certificate_id: certificate.id.clone(),
+            to_certificate_id: new_certificate_id.clone(),
+            from_version: upgrade_request.from_version.clone(),
+            to_version: upgrade_request.to_version.clone(),
+            old_metadata_uri: certificate.metadata_uri.clone(),
+            new_metadata_uri: new_metadata_uri.clone(),
+            old_metadata_hash: certificate.metadata_hash.clone(),
+            new_metadata_hash: new_metadata_hash.clone(),
+            metadata_changed: new_metadata_uri != certificate.metadata_uri
+                || new_metadata_hash != certificate.metadata_hash,
+            migration_script_hash: upgrade_request.migration_script_hash.clone(),
+            migration_data: upgrade_request.migration_data.clone(),
+            migrated_at: env.ledger().timestamp(),
+        };
+        env.storage().instance().set(
+            &DataKey::MetadataMigration(new_certificate_id.clone()),
+            &migration,
+        );
+
+        // Complete upgrade request
+        upgrade_request.status = UpgradeStatus::Completed;
+        upgrade_request.completed_at = Some(env.ledger().timestamp());
+        Self::save_upgrade_request(env, upgrade_request);
+
+        if migration.metadata_changed {
+            env.events().publish(
+                (symbol_short!("meta_mig"),),
+                MetadataMigratedEvent {
+                    upgrade_id: upgrade_id.clone(),
+                    certificate_id: new_certificate_id.clone(),
+                    old_metadata_uri: migration.old_metadata_uri,
+                    new_metadata_uri: migration.new_metadata_uri,
+                    new_metadata_hash: migration.new_metadata_hash,
+                    migration_script_hash: migration.migration_script_hash,
+                },
+            );
+        }
+
+        // Emit upgrade completed event
+        env.events().publish(
+            (Symbol::new(env, "upgrade_complete"),),
+            UpgradeCompletedEvent {
+                upgrade_id: upgrade_id.clone(),
+                certificate_id: certificate.id.clone(),
+                from_version: upgrade_request.from_version.clone(),
+                to_version: upgrade_request.to_version.clone(),
+                completed_at: upgrade_request.completed_at.unwrap(),
+                new_certificate_id: new_certificate_id.clone(),
+            },
+        );
+
+        // Emit certificate upgraded event
+        env.events().publish(
+            (Symbol::new(env, "cert_upgrade"),),
+            CertificateUpgradedEvent {
+                certificate_id: new_certificate_id.clone(),
+                from_version: upgrade_request.from_version.clone(),
+                to_version: upgrade_request.to_version.clone(),
+                upgraded_by: executor,
+                upgraded_at: upgrade_request.completed_at.unwrap(),
+                parent_certificate_id: Some(certificate.id),
+            },
+        );
+
+        Ok(new_certificate_id)
+    }
+
+    /// Archive a certificate version
+    fn archive_certificate_version(
+        env: &Env,
+        certificate_id: String,
+        version: CertificateVersion,
+        archiver: Address,
+        reason: String,
+    ) -> Result<(), CertificateError> {
+        // Get the certificate to archive
+        let certificate: Certificate = env
+            .storage()
+            .instance()
+            .get(&certificate_id)
+            .ok_or(CertificateError::NotFound)?;
+        
+        // Verify version matches
         if !certificate.version.is_equal(&version) {
             return Err(CertificateError::VersionConflict);
         }
         
-        // Serialize certificate data (simplified representation)
-        let original_data = certificate.metadata_uri; // Using metadata as data representation
-        
         // Create archived certificate
         let archived = ArchivedCertificate {
             certificate_id: certificate_id.clone(),
             version: version.clone(),
             archived_at: env.ledger().timestamp(),
             archived_by: archiver,
-            original_data,
-            reason: String::from_str(env, &reason),
+            certificate,
+            reason: reason.clone(),
         };
         
         // Store archived certificate
-        let archive_key = DataKey::ArchivedCertificate(certificate_id.clone(), version);
+        let archive_key = DataKey::ArchivedCertificate(certificate_id.clone(), version.clone());
         env.storage().instance().set(&archive_key, &archived);
         
         // Add to version chain
@@ -580,7 +1801,7 @@ impl CertificateContract {
         
         // Emit archived event
         env.events().publish(
-            (symbol_short!("cert_archive"),),
+            (Symbol::new(env, "cert_archive"),),
             CertificateArchivedEvent {
                 certificate_id: archived.certificate_id,
                 version: archived.version,
@@ -596,12 +1817,105 @@ impl CertificateContract {
 
 #[contractimpl]
 impl CertificateContract {
+    /// Set the contract administrator (one-time)
+    pub fn initialize(env: Env, admin: Address) {
+        admin.require_auth();
+
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("Contract already initialized");
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    /// Configure the token, collector and prices used for paid verification
+    pub fn set_fee_config(
+        env: Env,
+        token: Address,
+        collector: Address,
+        base_fee: i128,
+        fee_per_certificate: i128,
+    ) -> Result<(), CertificateError> {
+        Self::require_admin(&env);
+
+        if base_fee < 0 || fee_per_certificate < 0 {
+            return Err(CertificateError::InvalidFee);
+        }
+
+        env.storage().instance().set(
+            &DataKey::FeeConfig,
+            &FeeConfig {
+                token,
+                collector,
+                base_fee,
+                fee_per_certificate,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Configure the token transfer fees are escrowed in and who receives them
+    pub fn set_transfer_fee_config(env: Env, token: Address, policy: TransferFeePolicy) {
+        Self::require_admin(&env);
+
+        env.storage().instance().set(
+            &DataKey::TransferFeeConfig,
+            &TransferFeeConfig { token, policy },
+        );
+    }
+
+    /// Get the current transfer fee configuration
+    pub fn get_transfer_fee_config(env: Env) -> Result<TransferFeeConfig, CertificateError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TransferFeeConfig)
+            .ok_or(CertificateError::FeeNotConfigured)
+    }
+
+    /// Get the current verification fee configuration
+    pub fn get_fee_config(env: Env) -> Result<FeeConfig, CertificateError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::FeeConfig)
+            .ok_or(CertificateError::FeeNotConfigured)
+    }
+
+    /// Link the CRL contract that mirrors revocations (admin only)
+    ///
+    /// Issuers must be registered in the CRL contract and delegate this
+    /// contract as a CRL signer before revoking.
+    pub fn set_crl_contract(env: Env, crl_contract: Address) {
+        Self::require_admin(&env);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::CrlContract, &crl_contract);
+    }
+
+    pub fn get_crl_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::CrlContract)
+    }
+
     pub fn issue_certificate(
         env: Env,
         id: String,
         issuer: Address,
         owner: Address,
         metadata_uri: String,
+    ) {
+        let transfer_policy = Self::get_issuer_transfer_policy(env.clone(), issuer.clone());
+        Self::issue_certificate_with_policy(env, id, issuer, owner, metadata_uri, transfer_policy);
+    }
+
+    /// Issue a certificate with an explicit transfer policy, overriding the issuer default
+    pub fn issue_certificate_with_policy(
+        env: Env,
+        id: String,
+        issuer: Address,
+        owner: Address,
+        metadata_uri: String,
+        transfer_policy: TransferPolicy,
     ) {
         issuer.require_auth();
 
@@ -614,6 +1928,7 @@ impl CertificateContract {
             issuer,
             owner,
             metadata_uri,
+            metadata_hash: None,
             issued_at: env.ledger().timestamp(),
             revoked: false,
             revocation_reason: None,
@@ -624,6 +1939,7 @@ impl CertificateContract {
                 major: 1,
                 minor: 0,
                 patch: 0,
+                pre_release: None,
                 build: None,
             },
             parent_certificate_id: None,
@@ -635,20 +1951,38 @@ impl CertificateContract {
                     major: 1,
                     minor: 0,
                     patch: 0,
+                    pre_release: None,
                     build: None,
                 },
                 compatible_versions: Vec::new(&env),
                 backward_compatible: true,
                 forward_compatible: true,
             },
+            superseded: false,
             // Initialize freeze fields
             frozen: false,
-            freeze_info: None,
+            transfer_policy,
         };
 
         env.storage().instance().set(&id, &cert);
     }
 
+    /// Set the transfer policy applied to an issuer's future certificates
+    pub fn set_issuer_transfer_policy(env: Env, issuer: Address, policy: TransferPolicy) {
+        issuer.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::IssuerTransferPolicy(issuer), &policy);
+    }
+
+    /// Get an issuer's default transfer policy (`Free` unless set)
+    pub fn get_issuer_transfer_policy(env: Env, issuer: Address) -> TransferPolicy {
+        env.storage()
+            .instance()
+            .get(&DataKey::IssuerTransferPolicy(issuer))
+            .unwrap_or(TransferPolicy::Free)
+    }
+
     pub fn revoke_certificate(env: Env, id: String, reason: String) {
         let mut cert: Certificate = env
             .storage()
@@ -663,11 +1997,14 @@ impl CertificateContract {
         }
 
         cert.revoked = true;
-        cert.revocation_reason = Some(reason);
+        cert.revocation_reason = Some(reason.clone());
         cert.revoked_at = Some(env.ledger().timestamp());
         cert.revoked_by = Some(cert.issuer.clone());
 
         env.storage().instance().set(&id, &cert);
+        if Self::propagate_revocation(&env, &cert, &reason).is_err() {
+            panic!("Issuer has no CRL in the configured CRL contract");
+        }
     }
 
     /// Freeze a certificate temporarily during a dispute
@@ -700,8 +2037,8 @@ impl CertificateContract {
             panic!("Certificate is already frozen");
         }
 
-        // Check if certificate is revoked
-        if cert.revoked {
+        // Check if certificate is revoked, here or in the CRL
+        if Self::is_effectively_revoked(&env, &cert) {
             panic!("Cannot freeze a revoked certificate");
         }
 
@@ -733,7 +2070,6 @@ impl CertificateContract {
 
         // Update certificate
         cert.frozen = true;
-        cert.freeze_info = Some(freeze_info.clone());
 
         env.storage().instance().set(&id, &cert);
 
@@ -792,7 +2128,6 @@ impl CertificateContract {
 
         // Update certificate
         cert.frozen = false;
-        cert.freeze_info = None;
 
         env.storage().instance().set(&id, &cert);
 
@@ -822,7 +2157,7 @@ impl CertificateContract {
     /// 
     /// # Returns
     /// * `u32` - Number of certificates that were auto-unfrozen
-    pub fn process_auto_unfreeze(env: Env) -> u32 {
+    pub fn process_auto_unfreeze(_env: Env) -> u32 {
         // This would require iterating through all certificates
         // For efficiency, in production you'd maintain a separate index of frozen certificates
         // For now, return 0 as this requires more complex storage management
@@ -841,12 +2176,12 @@ impl CertificateContract {
 
     /// Get freeze information for a certificate
     pub fn get_freeze_info(env: Env, id: String) -> Option<FrozenCertificateInfo> {
-        let cert: Certificate = env
-            .storage()
+        if !env.storage().instance().has(&id) {
+            panic!("Certificate not found");
+        }
+        env.storage()
             .instance()
-            .get(&id)
-            .expect("Certificate not found");
-        cert.freeze_info
+            .get(&DataKey::FrozenCertificate(id))
     }
 
     /// Override unfreeze - allows admin to unfreeze even before the freeze period ends
@@ -875,7 +2210,6 @@ impl CertificateContract {
 
         // Update certificate
         cert.frozen = false;
-        cert.freeze_info = None;
 
         env.storage().instance().set(&id, &cert);
 
@@ -906,7 +2240,7 @@ impl CertificateContract {
             .instance()
             .get(&id)
             .expect("Certificate not found");
-        cert.revoked
+        Self::is_effectively_revoked(&env, &cert)
     }
 
     pub fn get_certificate(env: Env, id: String) -> Certificate {
@@ -947,6 +2281,8 @@ impl CertificateContract {
                     id,
                     exists: false,
                     revoked: false,
+                    is_latest: false,
+                    latest_id: None,
                     message: String::from_str(&env, "Certificate not found"),
                 };
                 failed += 1;
@@ -959,13 +2295,16 @@ impl CertificateContract {
                 .instance()
                 .get(&id)
                 .expect("Certificate should exist");
-            let revoked = cert.revoked;
+            let revoked = Self::is_effectively_revoked(&env, &cert);
+            let latest_id = Self::superseding_certificate_id(&env, &cert);
 
             if revoked {
                 let result = SingleVerificationResult {
                     id,
                     exists: true,
                     revoked: true,
+                    is_latest: latest_id.is_none(),
+                    latest_id,
                     message: String::from_str(&env, "Certificate is revoked"),
                 };
                 failed += 1;
@@ -975,6 +2314,8 @@ impl CertificateContract {
                     id,
                     exists: true,
                     revoked: false,
+                    is_latest: latest_id.is_none(),
+                    latest_id,
                     message: String::from_str(&env, "Certificate is valid"),
                 };
                 successful += 1;
@@ -982,8 +2323,7 @@ impl CertificateContract {
             }
         }
 
-        let total_cost =
-            BASE_VERIFICATION_COST + (COST_PER_CERTIFICATE * (count as u64));
+        let total_cost = Self::batch_verification_cost(count);
 
         BatchVerificationResult {
             results,
@@ -994,63 +2334,418 @@ impl CertificateContract {
         }
     }
 
-    pub fn verify_merkle_batch(
+    /// Verify a batch of certificates and collect the configured fee from `verifier`
+    ///
+    /// The fee (`base_fee + fee_per_certificate * ids.len()`) is transferred from
+    /// the verifier to the fee collector before verification. Empty batches are free.
+    pub fn batch_verify_certificates_paid(
+        env: Env,
+        verifier: Address,
+        ids: Vec<String>,
+    ) -> Result<PaidBatchVerificationResult, CertificateError> {
+        verifier.require_auth();
+
+        let config: FeeConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeConfig)
+            .ok_or(CertificateError::FeeNotConfigured)?;
+
+        let count = ids.len();
+        if count > MAX_BATCH_SIZE {
+            panic!("Batch size exceeds maximum supported certificates");
+        }
+
+        let fee = if count == 0 {
+            0
+        } else {
+            config
+                .fee_per_certificate
+                .checked_mul(count as i128)
+                .and_then(|per_certificate| per_certificate.checked_add(config.base_fee))
+                .ok_or(CertificateError::FeeOverflow)?
+        };
+
+        if fee > 0 {
+            let token_client = token::Client::new(&env, &config.token);
+            if token_client.balance(&verifier) < fee {
+                return Err(CertificateError::InsufficientBalance);
+            }
+            token_client.transfer(&verifier, &config.collector, &fee);
+
+            env.events().publish(
+                (symbol_short!("fee_paid"),),
+                VerificationFeePaidEvent {
+                    verifier,
+                    token: config.token.clone(),
+                    collector: config.collector,
+                    amount: fee,
+                    certificate_count: count,
+                    paid_at: env.ledger().timestamp(),
+                },
+            );
+        }
+
+        Ok(PaidBatchVerificationResult {
+            verification: Self::batch_verify_certificates(env, ids),
+            fee_token: config.token,
+            fee_paid: fee,
+        })
+    }
+
+    /// Verify a batch of certificates presented by a single holder
+    ///
+    /// Besides existence and revocation, each item reports whether the certificate
+    /// is frozen, who issued it, its version and whether it is owned by
+    /// `expected_owner`, together with a `VerificationStatus` code.
+    pub fn batch_verify_for_owner(
+        env: Env,
+        ids: Vec<String>,
+        expected_owner: Address,
+    ) -> OwnerBatchVerificationResult {
+        let count = ids.len();
+        if count > MAX_BATCH_SIZE {
+            panic!("Batch size exceeds maximum supported certificates");
+        }
+
+        let mut results: Vec<OwnerVerificationResult> = Vec::new(&env);
+        let mut successful: u32 = 0;
+        let mut failed: u32 = 0;
+
+        for id in ids.iter() {
+            let result = Self::verify_for_owner(&env, id, &expected_owner);
+            if result.status == VerificationStatus::Valid {
+                successful += 1;
+            } else {
+                failed += 1;
+            }
+            results.push_back(result);
+        }
+
+        let total_cost = if count == 0 {
+            0
+        } else {
+            Self::batch_verification_cost(count)
+        };
+
+        OwnerBatchVerificationResult {
+            expected_owner,
+            results,
+            total: count,
+            successful,
+            failed,
+            total_cost,
+        }
+    }
+
+    pub fn verify_merkle_batch(
+        env: Env,
+        root: BytesN<32>,
+        proofs: Vec<MerkleProof>,
+    ) -> Vec<MerkleVerificationResult> {
+        let count = proofs.len();
+
+        if count == 0 {
+            return Vec::new(&env);
+        }
+
+        if count > MAX_BATCH_SIZE {
+            panic!("Batch size exceeds maximum supported proofs");
+        }
+
+        // Only anchored, non-revoked roots from currently authorized issuers are trusted
+        let (anchored, trusted) = Self::trusted_batch_root(&env, &root);
+
+        let mut results: Vec<MerkleVerificationResult> = Vec::new(&env);
+
+        for i in 0..count {
+            let proof = proofs.get(i).unwrap();
+            let is_valid = trusted && Self::verify_single_merkle_proof(&env, &root, &proof);
+
+            let result = MerkleVerificationResult {
+                leaf: proof.leaf.clone(),
+                is_valid,
+                issuer: anchored.as_ref().map(|batch| batch.issuer.clone()),
+                batch_label: anchored.as_ref().map(|batch| batch.batch_label.clone()),
+            };
+            results.push_back(result);
+        }
+
+        results
+    }
+
+    /// Allow an issuer to anchor batch Merkle roots (admin only)
+    pub fn authorize_issuer(env: Env, issuer: Address) {
+        Self::require_admin(&env);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::AuthorizedIssuer(issuer), &true);
+    }
+
+    /// Withdraw an issuer's authorization; its anchored roots stop verifying (admin only)
+    pub fn deauthorize_issuer(env: Env, issuer: Address) {
+        Self::require_admin(&env);
+
+        env.storage()
+            .instance()
+            .remove(&DataKey::AuthorizedIssuer(issuer));
+    }
+
+    /// Check whether an issuer may anchor batch roots
+    pub fn is_authorized_issuer(env: Env, issuer: Address) -> bool {
+        Self::is_issuer_authorized(&env, &issuer)
+    }
+
+    /// Anchor a batch Merkle root so its proofs can be verified on-chain
+    pub fn anchor_batch_root(
+        env: Env,
+        issuer: Address,
+        root: BytesN<32>,
+        batch_label: String,
+        leaf_count: u32,
+    ) -> Result<AnchoredBatchRoot, CertificateError> {
+        issuer.require_auth();
+
+        if !Self::is_issuer_authorized(&env, &issuer) {
+            return Err(CertificateError::IssuerNotAuthorized);
+        }
+
+        if leaf_count == 0 {
+            return Err(CertificateError::InvalidData);
+        }
+
+        let root_key = DataKey::BatchRoot(root.clone());
+        if env.storage().persistent().has(&root_key) {
+            return Err(CertificateError::RootAlreadyAnchored);
+        }
+
+        let anchored = AnchoredBatchRoot {
+            root: root.clone(),
+            issuer: issuer.clone(),
+            batch_label: batch_label.clone(),
+            leaf_count,
+            anchored_at: env.ledger().timestamp(),
+            revoked: false,
+            revoked_at: None,
+        };
+        Self::persist_batch_entry(&env, &root_key, &anchored);
+
+        env.events().publish(
+            (symbol_short!("root_anch"),),
+            BatchRootAnchoredEvent {
+                root,
+                issuer,
+                batch_label,
+                leaf_count,
+                anchored_at: anchored.anchored_at,
+            },
+        );
+
+        Ok(anchored)
+    }
+
+    /// Revoke a whole anchored batch; none of its proofs will verify afterwards
+    pub fn revoke_batch_root(
+        env: Env,
+        issuer: Address,
+        root: BytesN<32>,
+    ) -> Result<(), CertificateError> {
+        issuer.require_auth();
+
+        let root_key = DataKey::BatchRoot(root.clone());
+        let mut anchored: AnchoredBatchRoot = env
+            .storage()
+            .persistent()
+            .get(&root_key)
+            .ok_or(CertificateError::NotFound)?;
+
+        if anchored.issuer != issuer {
+            return Err(CertificateError::Unauthorized);
+        }
+
+        if anchored.revoked {
+            return Err(CertificateError::AlreadyRevoked);
+        }
+
+        anchored.revoked = true;
+        anchored.revoked_at = Some(env.ledger().timestamp());
+        Self::persist_batch_entry(&env, &root_key, &anchored);
+
+        env.events().publish(
+            (symbol_short!("root_rev"),),
+            BatchRootRevokedEvent {
+                root,
+                issuer,
+                revoked_at: env.ledger().timestamp(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Revoke a single certificate within an anchored batch
+    pub fn revoke_batch_leaf(
+        env: Env,
+        issuer: Address,
+        root: BytesN<32>,
+        leaf_index: u32,
+    ) -> Result<(), CertificateError> {
+        issuer.require_auth();
+
+        let anchored: AnchoredBatchRoot = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BatchRoot(root.clone()))
+            .ok_or(CertificateError::NotFound)?;
+
+        if anchored.issuer != issuer {
+            return Err(CertificateError::Unauthorized);
+        }
+
+        if leaf_index >= anchored.leaf_count {
+            return Err(CertificateError::InvalidData);
+        }
+
+        let (word_key, mask) = Self::batch_revocation_slot(&root, leaf_index);
+        let word: u128 = env.storage().persistent().get(&word_key).unwrap_or(0);
+        if word & mask != 0 {
+            return Err(CertificateError::AlreadyRevoked);
+        }
+        Self::persist_batch_entry(&env, &word_key, &(word | mask));
+
+        env.events().publish(
+            (symbol_short!("leaf_rev"),),
+            BatchLeafRevokedEvent {
+                root,
+                leaf_index,
+                revoked_by: issuer,
+                revoked_at: env.ledger().timestamp(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Check whether a single certificate within a batch has been revoked
+    pub fn is_batch_leaf_revoked(env: Env, root: BytesN<32>, leaf_index: u32) -> bool {
+        let (word_key, mask) = Self::batch_revocation_slot(&root, leaf_index);
+        let word: u128 = env.storage().persistent().get(&word_key).unwrap_or(0);
+        word & mask != 0
+    }
+
+    /// Verify a certificate issued as a leaf of an anchored batch
+    ///
+    /// `leaf_data` is the XDR encoding of a `BatchCertificateLeaf`; the proof's
+    /// `leaf` must be its SHA-256 hash. Malformed encodings are rejected with
+    /// `InvalidData`; proof, root and revocation failures are reported in the result.
+    pub fn verify_batch_certificate(
+        env: Env,
+        root: BytesN<32>,
+        leaf_data: Bytes,
+        proof: MerkleProof,
+    ) -> Result<BatchCertificateVerification, CertificateError> {
+        let leaf = BatchCertificateLeaf::from_xdr(&env, &leaf_data)
+            .map_err(|_| CertificateError::InvalidData)?;
+
+        let (anchored, trusted) = Self::trusted_batch_root(&env, &root);
+        let leaf_hash: BytesN<32> = env.crypto().sha256(&leaf_data).to_bytes();
+        let proof_valid = trusted
+            && proof.leaf == leaf_hash
+            && Self::verify_single_merkle_proof(&env, &root, &proof);
+        // The claimed index must be the position the proof actually walks from,
+        // otherwise per-leaf revocation could be dodged with a mislabelled leaf
+        let in_position = match &anchored {
+            Some(batch) => {
+                leaf.leaf_index < batch.leaf_count
+                    && Self::batch_leaf_path(leaf.leaf_index, batch.leaf_count)
+                        == (proof.directions, proof.siblings.len())
+            }
+            None => false,
+        };
+        let leaf_revoked = Self::is_batch_leaf_revoked(env.clone(), root.clone(), leaf.leaf_index);
+
+        let status = if !proof_valid || !in_position {
+            VerificationStatus::InvalidProof
+        } else if leaf_revoked {
+            VerificationStatus::Revoked
+        } else {
+            VerificationStatus::Valid
+        };
+
+        Ok(BatchCertificateVerification {
+            is_valid: status == VerificationStatus::Valid,
+            status,
+            root,
+            issuer: anchored.as_ref().map(|batch| batch.issuer.clone()),
+            batch_label: anchored.as_ref().map(|batch| batch.batch_label.clone()),
+            leaf_index: leaf.leaf_index,
+            id: leaf.id,
+            owner: leaf.owner,
+            metadata_uri: leaf.metadata_uri,
+            issued_at: leaf.issued_at,
+            leaf_revoked,
+        })
+    }
+
+    /// Get an anchored batch root
+    pub fn get_batch_root(
         env: Env,
         root: BytesN<32>,
-        proofs: Vec<MerkleProof>,
-    ) -> Vec<MerkleVerificationResult> {
-        let count = proofs.len();
+    ) -> Result<AnchoredBatchRoot, CertificateError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BatchRoot(root))
+            .ok_or(CertificateError::NotFound)
+    }
 
-        if count == 0 {
-            return Vec::new(&env);
+    fn verify_single_merkle_proof(
+        env: &Env,
+        root: &BytesN<32>,
+        proof: &MerkleProof,
+    ) -> bool {
+        let count = proof.siblings.len();
+        if count > MAX_MERKLE_DEPTH {
+            return false;
         }
 
-        if count > MAX_BATCH_SIZE {
-            panic!("Batch size exceeds maximum supported proofs");
+        // Direction bits beyond the proof length would make proofs malleable
+        if count < MAX_MERKLE_DEPTH && (proof.directions >> count) != 0 {
+            return false;
         }
 
-        let mut results: Vec<MerkleVerificationResult> = Vec::new(&env);
+        let mut hash = Self::hash_merkle_leaf(env, &proof.leaf);
 
         for i in 0..count {
-            let proof = proofs.get(i).unwrap();
-            let is_valid = Self::verify_single_merkle_proof(
-                &env,
-                &root,
-                &proof.leaf,
-                &proof.siblings,
-            );
-
-            let result = MerkleVerificationResult {
-                leaf: proof.leaf.clone(),
-                is_valid,
+            let sibling = proof.siblings.get(i).unwrap();
+            hash = if (proof.directions >> i) & 1 == 1 {
+                Self::hash_merkle_node(env, &sibling, &hash)
+            } else {
+                Self::hash_merkle_node(env, &hash, &sibling)
             };
-            results.push_back(result);
         }
 
-        results
+        hash == *root
     }
 
-    fn verify_single_merkle_proof(
-        env: &Env,
-        root: &BytesN<32>,
-        leaf: &BytesN<32>,
-        siblings: &Vec<BytesN<32>>,
-    ) -> bool {
-        let mut hash = leaf.clone();
-        let count = siblings.len();
-
-        for i in 0..count {
-            let sibling = siblings.get(i).unwrap();
-            let mut data = Bytes::new(env);
-            data.append(&hash);
-            data.append(&sibling);
-            hash = env.crypto().sha256(&data);
-        }
+    /// Leaf hash: sha256(0x00 || leaf)
+    fn hash_merkle_leaf(env: &Env, leaf: &BytesN<32>) -> BytesN<32> {
+        let mut data = Bytes::new(env);
+        data.push_back(MERKLE_LEAF_PREFIX);
+        data.append(&Bytes::from(leaf.clone()));
+        env.crypto().sha256(&data).to_bytes()
+    }
 
-        hash == *root
+    /// Internal node hash: sha256(0x01 || left || right)
+    fn hash_merkle_node(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+        let mut data = Bytes::new(env);
+        data.push_back(MERKLE_NODE_PREFIX);
+        data.append(&Bytes::from(left.clone()));
+        data.append(&Bytes::from(right.clone()));
+        env.crypto().sha256(&data).to_bytes()
     }
 
-    // Request a certificate upgrade
+    // Request a certificate upgrade that keeps the current metadata
     pub fn request_upgrade(
         env: Env,
         upgrade_id: String,
@@ -1059,10 +2754,47 @@ impl CertificateContract {
         requester: Address,
         migration_data: Option<String>,
         notes: Option<String>,
+    ) -> Result<(), CertificateError> {
+        Self::request_upgrade_with_metadata(
+            env,
+            upgrade_id,
+            certificate_id,
+            to_version,
+            requester,
+            None,
+            None,
+            migration_data,
+            notes,
+        )
+    }
+
+    /// Request a certificate upgrade that migrates the metadata to a new URI
+    ///
+    /// `new_metadata_uri` and `new_metadata_hash` must be given together; the
+    /// upgraded certificate carries them and the change is recorded as a
+    /// `MetadataMigration`. Unless the issuer is the requester, a migration
+    /// always waits for the issuer's `approve_upgrade`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn request_upgrade_with_metadata(
+        env: Env,
+        upgrade_id: String,
+        certificate_id: String,
+        to_version: CertificateVersion,
+        requester: Address,
+        new_metadata_uri: Option<String>,
+        new_metadata_hash: Option<BytesN<32>>,
+        migration_data: Option<String>,
+        notes: Option<String>,
     ) -> Result<(), CertificateError> {
         // Authenticate requester
         requester.require_auth();
         
+        to_version.validate()?;
+
+        // A new metadata URI is only verifiable together with its SHA-256 content hash
+        if new_metadata_uri.is_some() != new_metadata_hash.is_some() {
+            return Err(CertificateError::InvalidData);
+        }
         // Check if upgrade request already exists
         let upgrade_key = DataKey::UpgradeRequest(upgrade_id.clone());
         if env.storage().instance().has(&upgrade_key) {
@@ -1076,8 +2808,8 @@ impl CertificateContract {
             .get(&certificate_id)
             .ok_or(CertificateError::NotFound)?;
         
-        // Check if certificate is revoked
-        if certificate.revoked {
+        // Check if certificate is revoked, here or in the CRL
+        if Self::is_effectively_revoked(&env, &certificate) {
             return Err(CertificateError::AlreadyRevoked);
         }
         
@@ -1086,21 +2818,28 @@ impl CertificateContract {
             return Err(CertificateError::CertificateNotUpgradable);
         }
         
-        // Check if there's already an upgrade in progress
+        // Check if there's already an upgrade in progress; lapsed ones are expired first
         let history_key = DataKey::UpgradeHistory(certificate_id.clone());
-        let upgrade_history: Vec<UpgradeRequest> = env
+        let previous_upgrades: Vec<UpgradeRequest> = env
             .storage()
             .instance()
             .get(&history_key)
             .unwrap_or(Vec::new(&env));
         
-        for upgrade in upgrade_history.iter() {
-            if upgrade.status == UpgradeStatus::Pending 
+        for mut upgrade in previous_upgrades.iter() {
+            if Self::is_upgrade_expired(&env, &upgrade) {
+                Self::expire_open_upgrade(&env, &mut upgrade);
+            } else if upgrade.status == UpgradeStatus::Pending 
                 || upgrade.status == UpgradeStatus::Approved 
                 || upgrade.status == UpgradeStatus::InProgress {
                 return Err(CertificateError::UpgradeInProgress);
             }
         }
+        let upgrade_history: Vec<UpgradeRequest> = env
+            .storage()
+            .instance()
+            .get(&history_key)
+            .unwrap_or(Vec::new(&env));
         
         // Validate upgrade path
         let upgrade_rule = Self::validate_upgrade_path(
@@ -1110,13 +2849,13 @@ impl CertificateContract {
             &certificate.upgrade_rules,
         )?;
         
-        // Check if issuer approval is required
-        let requires_approval = if let Some(rule) = upgrade_rule {
-            rule.requires_issuer_approval
-        } else {
-            // Default: major version changes require issuer approval
-            to_version.major > certificate.version.major
-        };
+        // Check if issuer approval is required; metadata migrations always need the
+        // issuer, either as the requester or through `approve_upgrade`
+        let requires_approval = Self::upgrade_requires_approval(
+            &certificate.version,
+            &to_version,
+            &upgrade_rule,
+        ) || (new_metadata_uri.is_some() && requester != certificate.issuer);
         
         // Create upgrade request
         let upgrade_request = UpgradeRequest {
@@ -1129,9 +2868,14 @@ impl CertificateContract {
             requested_at: env.ledger().timestamp(),
             approved_at: if requires_approval { None } else { Some(env.ledger().timestamp()) },
             completed_at: None,
+            expires_at: env.ledger().timestamp() + UPGRADE_EXPIRY_SECONDS,
             status: if requires_approval { UpgradeStatus::Pending } else { UpgradeStatus::Approved },
+            failure_code: None,
             migration_data,
             notes,
+            new_metadata_uri,
+            new_metadata_hash: new_metadata_hash.map(Bytes::from),
+            migration_script_hash: None,
         };
         
         // Store upgrade request
@@ -1164,13 +2908,13 @@ impl CertificateContract {
         
         // Emit upgrade requested event
         env.events().publish(
-            (symbol_short!("upgrade_request"),),
+            (Symbol::new(&env, "upgrade_request"),),
             UpgradeRequestedEvent {
                 upgrade_id: upgrade_id.clone(),
                 certificate_id,
                 from_version: upgrade_request.from_version,
                 to_version: upgrade_request.to_version,
-                requested_by: requester,
+                requested_by: requester.clone(),
                 requested_at: upgrade_request.requested_at,
             },
         );
@@ -1178,7 +2922,7 @@ impl CertificateContract {
         // If auto-approved, emit approval event
         if !requires_approval {
             env.events().publish(
-                (symbol_short!("upgrade_approve"),),
+                (Symbol::new(&env, "upgrade_approve"),),
                 UpgradeApprovedEvent {
                     upgrade_id,
                     approved_by: requester,
@@ -1191,6 +2935,7 @@ impl CertificateContract {
     }
 
     // Initiates a certificate transfer
+    #[allow(clippy::too_many_arguments)]
     pub fn initiate_transfer(
         env: Env,
         transfer_id: String,
@@ -1211,23 +2956,45 @@ impl CertificateContract {
         }
         
         // Get the certificate
-        let mut cert: Certificate = env.storage().instance().get(&certificate_id).ok_or(CertificateError::NotFound)?;
+        let cert: Certificate = env.storage().instance().get(&certificate_id).ok_or(CertificateError::NotFound)?;
         
         // Verify the sender is the current owner
         if cert.owner != from_address {
             return Err(CertificateError::Unauthorized);
         }
         
-        // Check if certificate is revoked
-        if cert.revoked {
+        // Check if certificate is revoked, here or in the CRL
+        if Self::is_effectively_revoked(&env, &cert) {
             return Err(CertificateError::AlreadyRevoked);
         }
         
+        // Soulbound certificates never change hands
+        if cert.transfer_policy == TransferPolicy::NonTransferable {
+            return Err(CertificateError::TransferNotAllowed);
+        }
+
         // Check if recipient is different from sender
         if from_address == to_address {
             return Err(CertificateError::InvalidData);
         }
         
+        // Only one open transfer per certificate; a lapsed one is swept first
+        let lock_key = DataKey::ActiveTransfer(certificate_id.clone());
+        if let Some(active_id) = env.storage().instance().get::<DataKey, String>(&lock_key) {
+            let mut active: TransferRequest = env
+                .storage()
+                .instance()
+                .get(&DataKey::TransferRequest(active_id))
+                .ok_or(CertificateError::TransferNotFound)?;
+            if !Self::is_transfer_expired(&env, &active) {
+                return Err(CertificateError::TransferLocked);
+            }
+            Self::expire_open_transfer(&env, &mut active);
+        }
+
+        // Escrow the transfer fee until the transfer completes or is abandoned
+        let fee_token = Self::escrow_transfer_fee(&env, &from_address, transfer_fee)?;
+
         // Create transfer request
         let transfer = TransferRequest {
             id: transfer_id.clone(),
@@ -1237,14 +3004,19 @@ impl CertificateContract {
             initiated_at: env.ledger().timestamp(),
             accepted_at: None,
             completed_at: None,
+            expires_at: env.ledger().timestamp() + TRANSFER_EXPIRY_SECONDS,
+            issuer_approved_by: None,
+            issuer_approved_at: None,
             status: TransferStatus::Pending,
             require_revocation,
             transfer_fee,
+            fee_token,
             memo,
         };
         
-        // Store the transfer request
+        // Store the transfer request and lock the certificate
         env.storage().instance().set(&transfer_key, &transfer);
+        env.storage().instance().set(&lock_key, &transfer_id);
         
         // Add to recipient's pending transfers
         let pending_key = DataKey::PendingTransfers(to_address.clone());
@@ -1266,7 +3038,7 @@ impl CertificateContract {
         
         // Emit transfer initiated event
         env.events().publish(
-            (symbol_short!("transfer_init"),),
+            (Symbol::new(&env, "transfer_init"),),
             TransferInitiatedEvent {
                 transfer_id: transfer_id.clone(),
                 certificate_id,
@@ -1290,11 +3062,10 @@ impl CertificateContract {
         approver.require_auth();
         
         // Get the upgrade request
-        let upgrade_key = DataKey::UpgradeRequest(upgrade_id.clone());
         let mut upgrade_request: UpgradeRequest = env
             .storage()
             .instance()
-            .get(&upgrade_key)
+            .get(&DataKey::UpgradeRequest(upgrade_id.clone()))
             .ok_or(CertificateError::NotFound)?;
         
         // Check if upgrade is pending approval
@@ -1302,6 +3073,11 @@ impl CertificateContract {
             return Err(CertificateError::UpgradeNotApproved);
         }
         
+        // Lapsed upgrades must be closed with `expire_upgrade` instead
+        if Self::is_upgrade_expired(&env, &upgrade_request) {
+            return Err(CertificateError::UpgradeExpired);
+        }
+
         // Get the certificate
         let certificate: Certificate = env
             .storage()
@@ -1314,31 +3090,18 @@ impl CertificateContract {
             return Err(CertificateError::Unauthorized);
         }
         
-        // Update upgrade request
+        // Update upgrade request and its history entry
         upgrade_request.status = UpgradeStatus::Approved;
         upgrade_request.approved_by = Some(approver.clone());
         upgrade_request.approved_at = Some(env.ledger().timestamp());
-        env.storage().instance().set(&upgrade_key, &upgrade_request);
+        Self::save_upgrade_request(&env, &upgrade_request);
         
         // Remove from pending upgrades
-        let pending_key = DataKey::PendingUpgrades(approver);
-        let mut pending_upgrades: Vec<String> = env
-            .storage()
-            .instance()
-            .get(&pending_key)
-            .unwrap_or(Vec::new(&env));
-        
-        let mut new_pending = Vec::new(&env);
-        for pending_id in pending_upgrades.iter() {
-            if pending_id != &upgrade_id {
-                new_pending.push_back(pending_id.clone());
-            }
-        }
-        env.storage().instance().set(&pending_key, &new_pending);
+        Self::remove_pending_upgrade(&env, &approver, &upgrade_id);
         
         // Emit upgrade approved event
         env.events().publish(
-            (symbol_short!("upgrade_approve"),),
+            (Symbol::new(&env, "upgrade_approve"),),
             UpgradeApprovedEvent {
                 upgrade_id,
                 approved_by: approver,
@@ -1354,16 +3117,15 @@ impl CertificateContract {
         env: Env,
         upgrade_id: String,
         executor: Address,
-    ) -> Result<String, CertificateError> {
+    ) -> Result<UpgradeOutcome, CertificateError> {
         // Authenticate executor
         executor.require_auth();
         
         // Get the upgrade request
-        let upgrade_key = DataKey::UpgradeRequest(upgrade_id.clone());
         let mut upgrade_request: UpgradeRequest = env
             .storage()
             .instance()
-            .get(&upgrade_key)
+            .get(&DataKey::UpgradeRequest(upgrade_id.clone()))
             .ok_or(CertificateError::NotFound)?;
         
         // Check if upgrade is approved
@@ -1371,8 +3133,13 @@ impl CertificateContract {
             return Err(CertificateError::UpgradeNotApproved);
         }
         
+        // Lapsed upgrades must be closed with `expire_upgrade` instead
+        if Self::is_upgrade_expired(&env, &upgrade_request) {
+            return Err(CertificateError::UpgradeExpired);
+        }
+
         // Get the certificate
-        let mut certificate: Certificate = env
+        let certificate: Certificate = env
             .storage()
             .instance()
             .get(&upgrade_request.certificate_id)
@@ -1382,92 +3149,261 @@ impl CertificateContract {
         if executor != certificate.owner && executor != certificate.issuer {
             return Err(CertificateError::Unauthorized);
         }
-        
-        // Check version compatibility
-        if !Self::check_compatibility(
+
+        // The certificate may have been revoked since the request was made
+        if Self::is_effectively_revoked(&env, &certificate) {
+            return Err(CertificateError::AlreadyRevoked);
+        }
+
+        // Metadata migrations must have been requested or approved by the issuer
+        if upgrade_request.new_metadata_uri.is_some()
+            && upgrade_request.approved_by.as_ref() != Some(&certificate.issuer)
+        {
+            return Err(CertificateError::Unauthorized);
+        }
+
+        // Rules can only narrow what is allowed; every path must also be compatible
+        let upgrade_rule = Self::validate_upgrade_path(
             &env,
             &certificate.version,
             &upgrade_request.to_version,
-        )? {
+            &certificate.upgrade_rules,
+        )?;
+        if !Self::check_compatibility(&env, &certificate.version, &upgrade_request.to_version)? {
             return Err(CertificateError::IncompatibleVersions);
         }
         
-        // Update upgrade request status
+        // Update upgrade request status and record the migration script being applied
         upgrade_request.status = UpgradeStatus::InProgress;
-        env.storage().instance().set(&upgrade_key, &upgrade_request);
-        
-        // Archive the current version
-        Self::archive_certificate_version(
-            &mut env,
-            certificate.id.clone(),
-            certificate.version.clone(),
-            executor.clone(),
-            String::from_str(&env, "Upgraded to newer version"),
-        )?;
-        
-        // Create new certificate with upgraded version
-        let new_certificate_id = String::from_str(&env, &format!("{}_v{}", certificate.id, 
-            upgrade_request.to_version.to_string(&env)));
-        
-        let new_certificate = Certificate {
-            id: new_certificate_id.clone(),
-            issuer: certificate.issuer.clone(),
-            owner: certificate.owner.clone(),
-            metadata_uri: certificate.metadata_uri.clone(),
-            issued_at: env.ledger().timestamp(),
-            revoked: false,
-            revocation_reason: None,
-            revoked_at: None,
-            revoked_by: None,
-            version: upgrade_request.to_version.clone(),
-            parent_certificate_id: Some(certificate.id.clone()),
-            child_certificate_id: None,
-            is_upgradable: certificate.is_upgradable,
-            upgrade_rules: certificate.upgrade_rules.clone(),
-            compatibility_matrix: certificate.compatibility_matrix.clone(),
-        };
-        
-        // Store new certificate
-        let new_cert_key = DataKey::Certificate(new_certificate_id.clone());
-        env.storage().instance().set(&new_cert_key, &new_certificate);
-        
-        // Update parent certificate's child reference
-        certificate.child_certificate_id = Some(new_certificate_id.clone());
-        let old_cert_key = DataKey::Certificate(certificate.id.clone());
-        env.storage().instance().set(&old_cert_key, &certificate);
-        
-        // Complete upgrade request
-        upgrade_request.status = UpgradeStatus::Completed;
-        upgrade_request.completed_at = Some(env.ledger().timestamp());
-        env.storage().instance().set(&upgrade_key, &upgrade_request);
+        upgrade_request.migration_script_hash =
+            upgrade_rule.and_then(|rule| rule.migration_script_hash);
+        Self::save_upgrade_request(&env, &upgrade_request);
         
-        // Emit upgrade completed event
         env.events().publish(
-            (symbol_short!("upgrade_complete"),),
-            UpgradeCompletedEvent {
+            (symbol_short!("upg_start"),),
+            UpgradeStartedEvent {
                 upgrade_id: upgrade_id.clone(),
-                certificate_id: certificate.id,
-                from_version: upgrade_request.from_version,
-                to_version: upgrade_request.to_version,
-                completed_at: upgrade_request.completed_at.unwrap(),
-                new_certificate_id: new_certificate_id.clone(),
+                started_by: executor.clone(),
+                started_at: env.ledger().timestamp(),
             },
         );
+
+        // A failed call would roll back the failure record along with everything
+        // else, so once started the outcome is reported through the request status
+        match Self::apply_upgrade(
+            &env,
+            &upgrade_id,
+            &mut upgrade_request,
+            certificate,
+            executor,
+        ) {
+            Ok(new_certificate_id) => Ok(UpgradeOutcome {
+                status: UpgradeStatus::Completed,
+                new_certificate_id: Some(new_certificate_id),
+                failure_code: None,
+            }),
+            Err(error) => {
+                let error_code = error as u32;
+                upgrade_request.status = UpgradeStatus::Failed;
+                upgrade_request.failure_code = Some(error_code);
+                Self::save_upgrade_request(&env, &upgrade_request);
+
+                env.events().publish(
+                    (symbol_short!("upg_fail"),),
+                    UpgradeFailedEvent {
+                        upgrade_id,
+                        error_code,
+                        failed_at: env.ledger().timestamp(),
+                    },
+                );
+
+                Ok(UpgradeOutcome {
+                    status: UpgradeStatus::Failed,
+                    new_certificate_id: None,
+                    failure_code: Some(error_code),
+                })
+            }
+        }
+    }
+
+    /// Cancel an open upgrade request (requester or issuer)
+    pub fn cancel_upgrade(
+        env: Env,
+        upgrade_id: String,
+        caller: Address,
+    ) -> Result<(), CertificateError> {
+        caller.require_auth();
+
+        let mut upgrade_request: UpgradeRequest = env
+            .storage()
+            .instance()
+            .get(&DataKey::UpgradeRequest(upgrade_id.clone()))
+            .ok_or(CertificateError::NotFound)?;
+
+        if upgrade_request.status != UpgradeStatus::Pending
+            && upgrade_request.status != UpgradeStatus::Approved
+        {
+            return Err(CertificateError::UpgradeNotOpen);
+        }
+        
+        let certificate: Certificate = env
+            .storage()
+            .instance()
+            .get(&upgrade_request.certificate_id)
+            .ok_or(CertificateError::NotFound)?;
+        if caller != upgrade_request.requested_by && caller != certificate.issuer {
+            return Err(CertificateError::Unauthorized);
+        }
+
+        Self::close_upgrade(&env, &mut upgrade_request, UpgradeStatus::Cancelled);
         
-        // Emit certificate upgraded event
         env.events().publish(
-            (symbol_short!("cert_upgrade"),),
-            CertificateUpgradedEvent {
-                certificate_id: new_certificate_id.clone(),
-                from_version: upgrade_request.from_version,
-                to_version: upgrade_request.to_version,
-                upgraded_by: executor,
-                upgraded_at: upgrade_request.completed_at.unwrap(),
-                parent_certificate_id: Some(certificate.id),
+            (symbol_short!("upg_cncl"),),
+            UpgradeCancelledEvent {
+                upgrade_id,
+                cancelled_by: caller,
+                cancelled_at: env.ledger().timestamp(),
             },
         );
         
-        Ok(new_certificate_id)
+        Ok(())
+    }
+
+    /// Expire a lapsed upgrade request (callable by anyone)
+    pub fn expire_upgrade(env: Env, upgrade_id: String) -> Result<(), CertificateError> {
+        let mut upgrade_request: UpgradeRequest = env
+            .storage()
+            .instance()
+            .get(&DataKey::UpgradeRequest(upgrade_id))
+            .ok_or(CertificateError::NotFound)?;
+
+        if !Self::is_upgrade_expired(&env, &upgrade_request) {
+            return Err(CertificateError::UpgradeNotOpen);
+        }
+
+        Self::expire_open_upgrade(&env, &mut upgrade_request);
+
+        Ok(())
+    }
+
+    /// Undo the upgrade that produced `certificate_id`
+    ///
+    /// Restores the parent from its archive as the current version, archives
+    /// and supersedes the child, and marks the upgrade request `RolledBack`.
+    /// Only the issuer may roll back, and only the head of a lineage whose
+    /// child and stored parent are neither revoked nor frozen, since the
+    /// archived parent predates both. An open transfer of the child blocks the
+    /// rollback with `TransferLocked` until it completes or lapses.
+    ///
+    /// The rolled-back child stays on record under its ID, so upgrading the
+    /// parent to that same version again fails with `VersionConflict`.
+    pub fn rollback_upgrade(env: Env, certificate_id: String) -> Result<String, CertificateError> {
+        let mut child: Certificate = env
+            .storage()
+            .instance()
+            .get(&certificate_id)
+            .ok_or(CertificateError::NotFound)?;
+        child.issuer.require_auth();
+
+        // Only the current head of a lineage can be rolled back
+        if child.superseded || child.child_certificate_id.is_some() {
+            return Err(CertificateError::VersionConflict);
+        }
+
+        // Restoring the parent would silently drop a revocation or freeze
+        if Self::is_effectively_revoked(&env, &child) {
+            return Err(CertificateError::AlreadyRevoked);
+        }
+        if Self::is_effectively_frozen(&env, &child) {
+            return Err(CertificateError::AlreadyFrozen);
+        }
+
+        let parent_id = child
+            .parent_certificate_id
+            .clone()
+            .ok_or(CertificateError::ParentVersionNotFound)?;
+
+        // Nor may it undo a revocation or freeze of the parent made after the upgrade
+        let stored_parent: Certificate = env
+            .storage()
+            .instance()
+            .get(&parent_id)
+            .ok_or(CertificateError::ParentVersionNotFound)?;
+        if Self::is_effectively_revoked(&env, &stored_parent) {
+            return Err(CertificateError::AlreadyRevoked);
+        }
+        if Self::is_effectively_frozen(&env, &stored_parent) {
+            return Err(CertificateError::AlreadyFrozen);
+        }
+
+        // An open transfer of the child must finish or lapse first
+        let lock_key = DataKey::ActiveTransfer(certificate_id.clone());
+        if let Some(active_id) = env.storage().instance().get::<DataKey, String>(&lock_key) {
+            let mut active: TransferRequest = env
+                .storage()
+                .instance()
+                .get(&DataKey::TransferRequest(active_id))
+                .ok_or(CertificateError::TransferNotFound)?;
+            if !Self::is_transfer_expired(&env, &active) {
+                return Err(CertificateError::TransferLocked);
+            }
+            Self::expire_open_transfer(&env, &mut active);
+        }
+
+        let migration: MetadataMigration = env
+            .storage()
+            .instance()
+            .get(&DataKey::MetadataMigration(certificate_id.clone()))
+            .ok_or(CertificateError::NotFound)?;
+        let archived: ArchivedCertificate = env
+            .storage()
+            .instance()
+            .get(&DataKey::ArchivedCertificate(
+                parent_id.clone(),
+                migration.from_version.clone(),
+            ))
+            .ok_or(CertificateError::ParentVersionNotFound)?;
+        let mut upgrade_request: UpgradeRequest = env
+            .storage()
+            .instance()
+            .get(&DataKey::UpgradeRequest(migration.upgrade_id.clone()))
+            .ok_or(CertificateError::NotFound)?;
+
+        // Keep the rolled-back version on record before superseding it
+        Self::archive_certificate_version(
+            &env,
+            certificate_id.clone(),
+            child.version.clone(),
+            child.issuer.clone(),
+            String::from_str(&env, "Upgrade rolled back"),
+        )?;
+        child.superseded = true;
+        env.storage().instance().set(&certificate_id, &child);
+
+        // Restore the parent exactly as archived, keeping ownership changes made since
+        let mut parent = archived.certificate;
+        parent.owner = child.owner.clone();
+        parent.child_certificate_id = None;
+        parent.superseded = false;
+        env.storage().instance().set(&parent_id, &parent);
+
+        upgrade_request.status = UpgradeStatus::RolledBack;
+        Self::save_upgrade_request(&env, &upgrade_request);
+
+        env.events().publish(
+            (symbol_short!("upg_rback"),),
+            UpgradeRolledBackEvent {
+                upgrade_id: upgrade_request.id,
+                certificate_id: parent_id.clone(),
+                rolled_back_certificate_id: certificate_id,
+                from_version: child.version,
+                to_version: parent.version,
+                rolled_back_by: child.issuer,
+                rolled_back_at: env.ledger().timestamp(),
+            },
+        );
+
+        Ok(parent_id)
     }
 
     // Accepts a certificate transfer
@@ -1491,37 +3427,53 @@ impl CertificateContract {
         if transfer.to_address != recipient {
             return Err(CertificateError::Unauthorized);
         }
-        
-        // Check if transfer is still pending
-        if transfer.status != TransferStatus::Pending {
-            return Err(CertificateError::TransferNotPending);
+        
+        // Check if transfer is still pending
+        if transfer.status != TransferStatus::Pending {
+            return Err(CertificateError::TransferNotPending);
+        }
+        
+        // Lapsed transfers must be swept with `expire_transfer` instead
+        if Self::is_transfer_expired(&env, &transfer) {
+            return Err(CertificateError::TransferExpired);
+        }
+
+        // The certificate may have been revoked or frozen since the transfer was initiated
+        let cert: Certificate = env
+            .storage()
+            .instance()
+            .get(&transfer.certificate_id)
+            .ok_or(CertificateError::NotFound)?;
+        if Self::is_effectively_revoked(&env, &cert) {
+            return Err(CertificateError::AlreadyRevoked);
+        }
+        if Self::is_effectively_frozen(&env, &cert) {
+            return Err(CertificateError::AlreadyFrozen);
         }
-        
+
         // Update transfer status
         transfer.status = TransferStatus::Accepted;
         transfer.accepted_at = Some(env.ledger().timestamp());
         env.storage().instance().set(&transfer_key, &transfer);
         
-        // Remove from pending transfers
-        let pending_key = DataKey::PendingTransfers(recipient.clone());
-        let mut pending_transfers: Vec<String> = env
+        // Move from pending to accepted transfers until the sender completes it
+        Self::remove_listed_transfer(
+            &env,
+            &DataKey::PendingTransfers(recipient.clone()),
+            &transfer_id,
+        );
+        let accepted_key = DataKey::AcceptedTransfers(recipient);
+        let mut accepted_transfers: Vec<String> = env
             .storage()
             .instance()
-            .get(&pending_key)
+            .get(&accepted_key)
             .unwrap_or(Vec::new(&env));
-        
-        // Remove this transfer from pending list
-        let mut new_pending = Vec::new(&env);
-        for pending_id in pending_transfers.iter() {
-            if pending_id != &transfer_id {
-                new_pending.push_back(pending_id.clone());
-            }
-        }
-        env.storage().instance().set(&pending_key, &new_pending);
+        accepted_transfers.push_back(transfer_id.clone());
+        env.storage().instance().set(&accepted_key, &accepted_transfers);
         
         // Emit transfer accepted event
         env.events().publish(
-            (symbol_short!("transfer_accept"),),
+            (Symbol::new(&env, "transfer_accept"),),
             TransferAcceptedEvent {
                 transfer_id: transfer_id.clone(),
                 accepted_at: transfer.accepted_at.unwrap(),
@@ -1531,6 +3483,68 @@ impl CertificateContract {
         Ok(())
     }
 
+    // Issuer co-signs a transfer of a certificate with the RequiresIssuerApproval policy
+    pub fn approve_transfer(
+        env: Env,
+        transfer_id: String,
+        issuer: Address,
+    ) -> Result<(), CertificateError> {
+        issuer.require_auth();
+
+        let transfer_key = DataKey::TransferRequest(transfer_id.clone());
+        let mut transfer: TransferRequest = env
+            .storage()
+            .instance()
+            .get(&transfer_key)
+            .ok_or(CertificateError::TransferNotFound)?;
+
+        let cert: Certificate = env
+            .storage()
+            .instance()
+            .get(&transfer.certificate_id)
+            .ok_or(CertificateError::NotFound)?;
+        if Self::is_effectively_revoked(&env, &cert) {
+            return Err(CertificateError::AlreadyRevoked);
+        }
+        if Self::is_effectively_frozen(&env, &cert) {
+            return Err(CertificateError::AlreadyFrozen);
+        }
+
+        if cert.issuer != issuer {
+            return Err(CertificateError::Unauthorized);
+        }
+
+        if cert.transfer_policy != TransferPolicy::RequiresIssuerApproval {
+            return Err(CertificateError::InvalidTransferStatus);
+        }
+
+        // Approval can be given while the transfer is still open
+        if transfer.status != TransferStatus::Pending && transfer.status != TransferStatus::Accepted
+        {
+            return Err(CertificateError::InvalidTransferStatus);
+        }
+
+        if Self::is_transfer_expired(&env, &transfer) {
+            return Err(CertificateError::TransferExpired);
+        }
+
+        transfer.issuer_approved_by = Some(issuer.clone());
+        transfer.issuer_approved_at = Some(env.ledger().timestamp());
+        env.storage().instance().set(&transfer_key, &transfer);
+
+        env.events().publish(
+            (symbol_short!("xfer_appr"),),
+            TransferIssuerApprovedEvent {
+                transfer_id,
+                certificate_id: transfer.certificate_id,
+                issuer,
+                approved_at: env.ledger().timestamp(),
+            },
+        );
+
+        Ok(())
+    }
+
     // Completes a certificate transfer (called after acceptance)
     pub fn complete_transfer(
         env: Env,
@@ -1553,12 +3567,25 @@ impl CertificateContract {
             return Err(CertificateError::InvalidTransferStatus);
         }
         
+        // Lapsed transfers must be swept with `expire_transfer` instead
+        if Self::is_transfer_expired(&env, &transfer) {
+            return Err(CertificateError::TransferExpired);
+        }
+
         // Get the certificate
         let mut cert: Certificate = env
             .storage()
             .instance()
             .get(&transfer.certificate_id)
             .ok_or(CertificateError::NotFound)?;
+
+        // The certificate may have been revoked or frozen since the transfer was accepted
+        if Self::is_effectively_revoked(&env, &cert) {
+            return Err(CertificateError::AlreadyRevoked);
+        }
+        if Self::is_effectively_frozen(&env, &cert) {
+            return Err(CertificateError::AlreadyFrozen);
+        }
         
         // Verify authorization (sender, recipient, or issuer can complete)
         if executor != transfer.from_address 
@@ -1567,13 +3594,26 @@ impl CertificateContract {
             return Err(CertificateError::Unauthorized);
         }
         
+        // Enforce the certificate's transfer policy
+        match cert.transfer_policy {
+            TransferPolicy::NonTransferable => return Err(CertificateError::TransferNotAllowed),
+            TransferPolicy::RequiresIssuerApproval => {
+                if transfer.issuer_approved_by.is_none() {
+                    return Err(CertificateError::IssuerApprovalRequired);
+                }
+            }
+            TransferPolicy::Free => {}
+        }
+
         // Revoke certificate if required
         if transfer.require_revocation {
+            let reason = String::from_str(&env, "Transferred to new owner");
             cert.revoked = true;
-            cert.revocation_reason = Some(String::from_str(&env, "Transferred to new owner"));
+            cert.revocation_reason = Some(reason.clone());
             cert.revoked_at = Some(env.ledger().timestamp());
             cert.revoked_by = Some(transfer.from_address.clone());
             env.storage().instance().set(&transfer.certificate_id, &cert);
+            Self::propagate_revocation(&env, &cert, &reason)?;
         }
         
         // Update certificate owner
@@ -1585,6 +3625,24 @@ impl CertificateContract {
         transfer.completed_at = Some(env.ledger().timestamp());
         env.storage().instance().set(&transfer_key, &transfer);
         
+        // Release the escrowed fee according to the configured policy
+        if transfer.fee_token.is_some() {
+            let policy = env
+                .storage()
+                .instance()
+                .get::<DataKey, TransferFeeConfig>(&DataKey::TransferFeeConfig)
+                .map(|config| config.policy)
+                .unwrap_or(TransferFeePolicy::PayIssuer);
+            match policy {
+                TransferFeePolicy::PayIssuer => {
+                    Self::release_transfer_fee(&env, &transfer, &cert.issuer, false)
+                }
+                TransferFeePolicy::RefundSender => {
+                    Self::release_transfer_fee(&env, &transfer, &transfer.from_address, true)
+                }
+            }
+        }
+
         // Add to transfer history
         let history_key = DataKey::TransferHistory(transfer.certificate_id.clone());
         let mut history: Vec<TransferHistory> = env
@@ -1601,14 +3659,19 @@ impl CertificateContract {
             transferred_at: transfer.completed_at.unwrap(),
             transfer_fee: transfer.transfer_fee,
             memo: transfer.memo.clone(),
+            issuer_approved_by: transfer.issuer_approved_by.clone(),
+            issuer_approved_at: transfer.issuer_approved_at,
         };
         
         history.push_back(transfer_history);
         env.storage().instance().set(&history_key, &history);
         
+        // Release the certificate lock
+        Self::close_transfer(&env, &transfer);
+
         // Emit transfer completed event
         env.events().publish(
-            (symbol_short!("transfer_complete"),),
+            (Symbol::new(&env, "transfer_complete"),),
             TransferCompletedEvent {
                 transfer_id: transfer_id.clone(),
                 certificate_id: transfer.certificate_id,
@@ -1653,25 +3716,15 @@ impl CertificateContract {
         transfer.status = TransferStatus::Rejected;
         env.storage().instance().set(&transfer_key, &transfer);
         
-        // Remove from pending transfers
-        let pending_key = DataKey::PendingTransfers(recipient);
-        let mut pending_transfers: Vec<String> = env
-            .storage()
-            .instance()
-            .get(&pending_key)
-            .unwrap_or(Vec::new(&env));
-        
-        let mut new_pending = Vec::new(&env);
-        for pending_id in pending_transfers.iter() {
-            if pending_id != &transfer_id {
-                new_pending.push_back(pending_id.clone());
-            }
-        }
-        env.storage().instance().set(&pending_key, &new_pending);
+        // Refund the escrowed fee to the sender
+        Self::release_transfer_fee(&env, &transfer, &transfer.from_address, true);
+
+        // Remove from pending transfers and release the certificate lock
+        Self::close_transfer(&env, &transfer);
         
         // Emit transfer rejected event
         env.events().publish(
-            (symbol_short!("transfer_reject"),),
+            (Symbol::new(&env, "transfer_reject"),),
             TransferRejectedEvent {
                 transfer_id,
                 rejected_at: env.ledger().timestamp(),
@@ -1712,25 +3765,15 @@ impl CertificateContract {
         transfer.status = TransferStatus::Cancelled;
         env.storage().instance().set(&transfer_key, &transfer);
         
-        // Remove from pending transfers
-        let pending_key = DataKey::PendingTransfers(transfer.to_address);
-        let mut pending_transfers: Vec<String> = env
-            .storage()
-            .instance()
-            .get(&pending_key)
-            .unwrap_or(Vec::new(&env));
-        
-        let mut new_pending = Vec::new(&env);
-        for pending_id in pending_transfers.iter() {
-            if pending_id != &transfer_id {
-                new_pending.push_back(pending_id.clone());
-            }
-        }
-        env.storage().instance().set(&pending_key, &new_pending);
+        // Refund the escrowed fee to the sender
+        Self::release_transfer_fee(&env, &transfer, &transfer.from_address, true);
+
+        // Remove from pending transfers and release the certificate lock
+        Self::close_transfer(&env, &transfer);
         
         // Emit transfer cancelled event
         env.events().publish(
-            (symbol_short!("transfer_cancel"),),
+            (Symbol::new(&env, "transfer_cancel"),),
             TransferCancelledEvent {
                 transfer_id,
                 cancelled_at: env.ledger().timestamp(),
@@ -1740,6 +3783,60 @@ impl CertificateContract {
         Ok(())
     }
 
+    // Expires a lapsed transfer, refunding any escrowed fee (callable by anyone)
+    pub fn expire_transfer(env: Env, transfer_id: String) -> Result<(), CertificateError> {
+        let mut transfer: TransferRequest = env
+            .storage()
+            .instance()
+            .get(&DataKey::TransferRequest(transfer_id))
+            .ok_or(CertificateError::TransferNotFound)?;
+
+        if transfer.status != TransferStatus::Pending && transfer.status != TransferStatus::Accepted
+        {
+            return Err(CertificateError::InvalidTransferStatus);
+        }
+
+        if !Self::is_transfer_expired(&env, &transfer) {
+            return Err(CertificateError::InvalidTransferStatus);
+        }
+
+        Self::expire_open_transfer(&env, &mut transfer);
+
+        Ok(())
+    }
+
+    // Expires every lapsed transfer open for a recipient, pending or accepted,
+    // returning how many were swept
+    pub fn sweep_expired_transfers(env: Env, recipient: Address) -> u32 {
+        let mut open: Vec<String> = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingTransfers(recipient.clone()))
+            .unwrap_or(Vec::new(&env));
+        let accepted: Vec<String> = env
+            .storage()
+            .instance()
+            .get(&DataKey::AcceptedTransfers(recipient))
+            .unwrap_or(Vec::new(&env));
+        open.append(&accepted);
+
+        let mut swept: u32 = 0;
+        for transfer_id in open.iter() {
+            let stored: Option<TransferRequest> = env
+                .storage()
+                .instance()
+                .get(&DataKey::TransferRequest(transfer_id));
+            if let Some(mut transfer) = stored {
+                if Self::is_transfer_expired(&env, &transfer) {
+                    Self::expire_open_transfer(&env, &mut transfer);
+                    swept += 1;
+                }
+            }
+        }
+
+        swept
+    }
+
     // Query functions
     
     // Get a transfer request by ID
@@ -1777,6 +3874,153 @@ impl CertificateContract {
             .unwrap_or(0)
     }
 
+    /// Add or replace a global upgrade rule, applied to every certificate
+    pub fn set_global_upgrade_rule(env: Env, rule: UpgradeRule) -> Result<(), CertificateError> {
+        Self::require_admin(&env);
+
+        rule.from_version.validate()?;
+        rule.to_version.validate()?;
+        if !rule.to_version.is_greater_than(&rule.from_version) {
+            return Err(CertificateError::VersionConflict);
+        }
+
+        let rules: Vec<UpgradeRule> = env
+            .storage()
+            .instance()
+            .get(&DataKey::UpgradeRules)
+            .unwrap_or(Vec::new(&env));
+        let mut updated = Vec::new(&env);
+        for existing in rules.iter() {
+            if !(existing.from_version.is_equal(&rule.from_version)
+                && existing.to_version.is_equal(&rule.to_version))
+            {
+                updated.push_back(existing);
+            }
+        }
+        updated.push_back(rule);
+        env.storage()
+            .instance()
+            .set(&DataKey::UpgradeRules, &updated);
+
+        Ok(())
+    }
+
+    /// Remove the global upgrade rule for a path
+    pub fn remove_global_upgrade_rule(
+        env: Env,
+        from_version: CertificateVersion,
+        to_version: CertificateVersion,
+    ) -> Result<(), CertificateError> {
+        Self::require_admin(&env);
+
+        let rules: Vec<UpgradeRule> = env
+            .storage()
+            .instance()
+            .get(&DataKey::UpgradeRules)
+            .unwrap_or(Vec::new(&env));
+        let mut updated = Vec::new(&env);
+        for existing in rules.iter() {
+            if !(existing.from_version.is_equal(&from_version)
+                && existing.to_version.is_equal(&to_version))
+            {
+                updated.push_back(existing);
+            }
+        }
+        if updated.len() == rules.len() {
+            return Err(CertificateError::NotFound);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::UpgradeRules, &updated);
+
+        Ok(())
+    }
+
+    /// Get the global upgrade rules
+    pub fn get_global_upgrade_rules(env: Env) -> Vec<UpgradeRule> {
+        env.storage()
+            .instance()
+            .get(&DataKey::UpgradeRules)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Set the compatibility matrix for a version
+    pub fn set_compatibility_matrix(
+        env: Env,
+        matrix: CompatibilityMatrix,
+    ) -> Result<(), CertificateError> {
+        Self::require_admin(&env);
+
+        env.storage().instance().set(
+            &DataKey::CompatibilityMatrix(matrix.version.clone()),
+            &matrix,
+        );
+
+        Ok(())
+    }
+
+    /// Remove the compatibility matrix for a version, restoring the same-major default
+    pub fn remove_compatibility_matrix(
+        env: Env,
+        version: CertificateVersion,
+    ) -> Result<(), CertificateError> {
+        Self::require_admin(&env);
+
+        let compatibility_key = DataKey::CompatibilityMatrix(version);
+        if !env.storage().instance().has(&compatibility_key) {
+            return Err(CertificateError::NotFound);
+        }
+        env.storage().instance().remove(&compatibility_key);
+
+        Ok(())
+    }
+
+    /// Replace a certificate's own upgrade rules; these override global rules
+    pub fn set_certificate_upgrade_rules(
+        env: Env,
+        certificate_id: String,
+        upgrade_rules: Vec<UpgradeRule>,
+    ) -> Result<(), CertificateError> {
+        let mut certificate: Certificate = env
+            .storage()
+            .instance()
+            .get(&certificate_id)
+            .ok_or(CertificateError::NotFound)?;
+        certificate.issuer.require_auth();
+
+        for rule in upgrade_rules.iter() {
+            rule.from_version.validate()?;
+            rule.to_version.validate()?;
+            if !rule.to_version.is_greater_than(&rule.from_version) {
+                return Err(CertificateError::VersionConflict);
+            }
+        }
+
+        certificate.upgrade_rules = upgrade_rules;
+        env.storage().instance().set(&certificate_id, &certificate);
+
+        Ok(())
+    }
+
+    /// Allow or forbid upgrades of a certificate
+    pub fn set_certificate_upgradable(
+        env: Env,
+        certificate_id: String,
+        is_upgradable: bool,
+    ) -> Result<(), CertificateError> {
+        let mut certificate: Certificate = env
+            .storage()
+            .instance()
+            .get(&certificate_id)
+            .ok_or(CertificateError::NotFound)?;
+        certificate.issuer.require_auth();
+
+        certificate.is_upgradable = is_upgradable;
+        env.storage().instance().set(&certificate_id, &certificate);
+
+        Ok(())
+    }
+
     // Query functions for upgrades
     
     // Get an upgrade request by ID
@@ -1788,6 +4032,48 @@ impl CertificateContract {
             .ok_or(CertificateError::NotFound)
     }
 
+    /// Get the metadata migration that produced an upgraded certificate
+    pub fn get_metadata_migration(
+        env: Env,
+        certificate_id: String,
+    ) -> Result<MetadataMigration, CertificateError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::MetadataMigration(certificate_id))
+            .ok_or(CertificateError::NotFound)
+    }
+
+    /// Get every version of a certificate, from the original issue to the current head
+    pub fn get_lineage(
+        env: Env,
+        certificate_id: String,
+    ) -> Result<Vec<Certificate>, CertificateError> {
+        let cert: Certificate = env
+            .storage()
+            .instance()
+            .get(&certificate_id)
+            .ok_or(CertificateError::NotFound)?;
+        Ok(Self::lineage_of(&env, cert))
+    }
+
+    /// Resolve any version of a certificate to the current head of its lineage
+    pub fn resolve_latest(
+        env: Env,
+        certificate_id: String,
+    ) -> Result<Certificate, CertificateError> {
+        let cert: Certificate = env
+            .storage()
+            .instance()
+            .get(&certificate_id)
+            .ok_or(CertificateError::NotFound)?;
+        if !cert.superseded {
+            return Ok(cert);
+        }
+        Self::lineage_of(&env, cert)
+            .last()
+            .ok_or(CertificateError::NotFound)
+    }
+
     // Get upgrade history for a certificate
     pub fn get_upgrade_history(env: Env, certificate_id: String) -> Vec<UpgradeRequest> {
         let history_key = DataKey::UpgradeHistory(certificate_id);
@@ -1850,13 +4136,30 @@ impl CertificateContract {
 
     // Helper function to compare versions
     pub fn compare_versions(
-        env: Env,
+        _env: Env,
         version1: CertificateVersion,
         version2: CertificateVersion,
     ) -> i32 {
         version1.compare(&version2)
     }
 
+    /// Parse a semantic version string such as "2.0.0-beta.1+build.5"
+    pub fn parse_version(
+        env: Env,
+        version: String,
+    ) -> Result<CertificateVersion, CertificateError> {
+        CertificateVersion::parse(&env, &version)
+    }
+
+    /// Format a version as a semantic version string
+    pub fn format_version(
+        env: Env,
+        version: CertificateVersion,
+    ) -> Result<String, CertificateError> {
+        version.validate()?;
+        Ok(version.format(&env))
+    }
+
     // Helper function to check if upgrade is allowed
     pub fn is_upgrade_allowed(
         env: Env,
@@ -1870,12 +4173,3 @@ impl CertificateContract {
 
 #[cfg(test)]
 mod test;
-
-#[cfg(test)]
-pub use crl_test::*;
-
-#[cfg(test)]
-pub use multisig_test::*;
-
-#[cfg(test)]
-pub use metadata_test::*;
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Env, String, Vec, symbol_short};
+use soroban_sdk::{contracttype, symbol_short, Env, String, Symbol, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -162,7 +162,7 @@ pub fn register_schema(
         .set(&MetadataDataKey::SchemaCount, &(count + 1));
 
     env.events().publish(
-        (symbol_short!("schema_reg"),),
+        (Symbol::new(env, "schema_reg"),),
         SchemaRegisteredEvent {
             schema_id: schema.id,
             name: schema.name,
@@ -266,7 +266,7 @@ pub fn validate_metadata(
                 }
 
                 if entry.value_type == MetadataFieldType::String {
-                    let len = entry.value.len() as u32;
+                    let len = entry.value.len();
                     if field_rule.min_length > 0 && len < field_rule.min_length {
                         errors.push_back(MetadataFieldError {
                             field: entry.key.clone(),
@@ -300,7 +300,7 @@ pub fn validate_metadata(
     let error_count = errors.len();
 
     env.events().publish(
-        (symbol_short!("meta_valid"),),
+        (Symbol::new(env, "meta_valid"),),
         MetadataValidatedEvent {
             certificate_id: certificate_id.clone(),
             schema_id: schema_id.clone(),
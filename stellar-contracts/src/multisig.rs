@@ -1,5 +1,4 @@
-#![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec, Map};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec};
 
 // Multi-sig configuration for an issuer
 #[contracttype]
@@ -71,7 +70,7 @@ pub struct MultisigEvent {
 pub struct SignatureResult {
     pub success: bool,
     pub message: String,
-    pub final_status: Option<RequestStatus>,
+    pub final_status: RequestStatus,
 }
 
 // Pagination parameters
@@ -113,10 +112,10 @@ impl MultisigCertificateContract {
         if threshold == 0 {
             panic!("Threshold must be greater than 0");
         }
-        if signers.len() == 0 {
+        if signers.is_empty() {
             panic!("Must provide at least one signer");
         }
-        if threshold > signers.len() as u32 {
+        if threshold > signers.len() {
             panic!("Threshold cannot exceed number of signers");
         }
         if max_signers == 0 || max_signers < threshold {
@@ -163,12 +162,12 @@ impl MultisigCertificateContract {
                 panic!("Threshold must be greater than 0");
             }
             if let Some(ref signers) = new_signers {
-                if threshold > signers.len() as u32 {
+                if threshold > signers.len() {
                     panic!("Threshold cannot exceed number of signers");
                 }
                 config.threshold = threshold;
                 config.signers = signers.clone();
-            } else if threshold > config.signers.len() as u32 {
+            } else if threshold > config.signers.len() {
                 panic!("Threshold cannot exceed number of signers");
             } else {
                 config.threshold = threshold;
@@ -176,10 +175,10 @@ impl MultisigCertificateContract {
         }
 
         if let Some(signers) = new_signers {
-            if signers.len() == 0 {
+            if signers.is_empty() {
                 panic!("Must provide at least one signer");
             }
-            if config.threshold > signers.len() as u32 {
+            if config.threshold > signers.len() {
                 panic!("Threshold cannot exceed number of signers");
             }
             config.signers = signers;
@@ -207,10 +206,13 @@ impl MultisigCertificateContract {
         expiration_days: u32,
     ) -> PendingRequest {
         // Verify that the issuer has a multisig configuration
-        let config: MultisigConfig = env.storage()
+        if !env
+            .storage()
             .instance()
-            .get(&self::DataKey::MultisigConfig(issuer.clone()))
-            .expect("Issuer does not have multisig configuration");
+            .has(&self::DataKey::MultisigConfig(issuer.clone()))
+        {
+            panic!("Issuer does not have multisig configuration");
+        }
 
         // Check if request already exists
         if env.storage().instance().has(&self::DataKey::PendingRequest(request_id.clone())) {
@@ -262,8 +264,8 @@ impl MultisigCertificateContract {
                 .set(&self::DataKey::PendingRequest(request_id.clone()), &request);
             return SignatureResult {
                 success: false,
-                message: "Request has expired".into(),
-                final_status: Some(RequestStatus::Expired),
+                message: String::from_str(&env, "Request has expired"),
+                final_status: RequestStatus::Expired,
             };
         }
 
@@ -271,8 +273,8 @@ impl MultisigCertificateContract {
         if request.status != RequestStatus::Pending {
             return SignatureResult {
                 success: false,
-                message: format!("Request is already {:?}", request.status).into(),
-                final_status: Some(request.status.clone()),
+                message: String::from_str(&env, "Request is no longer pending"),
+                final_status: request.status.clone(),
             };
         }
 
@@ -293,8 +295,8 @@ impl MultisigCertificateContract {
         if !is_authorized_signer {
             return SignatureResult {
                 success: false,
-                message: "Approver is not an authorized signer".into(),
-                final_status: Some(request.status.clone()),
+                message: String::from_str(&env, "Approver is not an authorized signer"),
+                final_status: request.status.clone(),
             };
         }
 
@@ -303,8 +305,8 @@ impl MultisigCertificateContract {
             if approved_by == approver {
                 return SignatureResult {
                     success: false,
-                    message: "Request already approved by this signer".into(),
-                    final_status: Some(request.status.clone()),
+                    message: String::from_str(&env, "Request already approved by this signer"),
+                    final_status: request.status.clone(),
                 };
             }
         }
@@ -314,8 +316,8 @@ impl MultisigCertificateContract {
             if rejected_by == approver {
                 return SignatureResult {
                     success: false,
-                    message: "Request already rejected by this signer".into(),
-                    final_status: Some(request.status.clone()),
+                    message: String::from_str(&env, "Request already rejected by this signer"),
+                    final_status: request.status.clone(),
                 };
             }
         }
@@ -324,7 +326,7 @@ impl MultisigCertificateContract {
         request.approvals.push_back(approver.clone());
 
         // Check if we have enough approvals
-        if request.approvals.len() >= config.threshold as u32 {
+        if request.approvals.len() >= config.threshold {
             request.status = RequestStatus::Approved;
         }
 
@@ -346,8 +348,8 @@ impl MultisigCertificateContract {
 
         SignatureResult {
             success: true,
-            message: format!("Request approved. {} approvals received.", request.approvals.len()).into(),
-            final_status: Some(request.status.clone()),
+            message: String::from_str(&env, "Request approved"),
+            final_status: request.status.clone(),
         }
     }
 
@@ -356,7 +358,7 @@ impl MultisigCertificateContract {
         env: Env,
         request_id: String,
         rejector: Address,
-        reason: Option<String>,
+        _reason: Option<String>,
     ) -> SignatureResult {
         rejector.require_auth();
 
@@ -374,8 +376,8 @@ impl MultisigCertificateContract {
                 .set(&self::DataKey::PendingRequest(request_id.clone()), &request);
             return SignatureResult {
                 success: false,
-                message: "Request has expired".into(),
-                final_status: Some(RequestStatus::Expired),
+                message: String::from_str(&env, "Request has expired"),
+                final_status: RequestStatus::Expired,
             };
         }
 
@@ -383,8 +385,8 @@ impl MultisigCertificateContract {
         if request.status != RequestStatus::Pending {
             return SignatureResult {
                 success: false,
-                message: format!("Request is already {:?}", request.status).into(),
-                final_status: Some(request.status.clone()),
+                message: String::from_str(&env, "Request is no longer pending"),
+                final_status: request.status.clone(),
             };
         }
 
@@ -405,8 +407,8 @@ impl MultisigCertificateContract {
         if !is_authorized_signer {
             return SignatureResult {
                 success: false,
-                message: "Rejector is not an authorized signer".into(),
-                final_status: Some(request.status.clone()),
+                message: String::from_str(&env, "Rejector is not an authorized signer"),
+                final_status: request.status.clone(),
             };
         }
 
@@ -415,8 +417,8 @@ impl MultisigCertificateContract {
             if rejected_by == rejector {
                 return SignatureResult {
                     success: false,
-                    message: "Request already rejected by this signer".into(),
-                    final_status: Some(request.status.clone()),
+                    message: String::from_str(&env, "Request already rejected by this signer"),
+                    final_status: request.status.clone(),
                 };
             }
         }
@@ -426,8 +428,8 @@ impl MultisigCertificateContract {
             if approved_by == rejector {
                 return SignatureResult {
                     success: false,
-                    message: "Signer already approved this request".into(),
-                    final_status: Some(request.status.clone()),
+                    message: String::from_str(&env, "Signer already approved this request"),
+                    final_status: request.status.clone(),
                 };
             }
         }
@@ -437,7 +439,7 @@ impl MultisigCertificateContract {
 
         // Check if we have enough rejections to make it impossible to reach threshold
         // (if remaining possible approvals can't reach threshold)
-        let total_signers = config.signers.len() as u32;
+        let total_signers = config.signers.len();
         let possible_approvals = total_signers - request.rejections.len();
         
         if possible_approvals < config.threshold {
@@ -462,8 +464,8 @@ impl MultisigCertificateContract {
 
         SignatureResult {
             success: true,
-            message: format!("Request rejected. {} rejections received.", request.rejections.len()).into(),
-            final_status: Some(request.status.clone()),
+            message: String::from_str(&env, "Request rejected"),
+            final_status: request.status.clone(),
         }
     }
 
@@ -496,7 +498,7 @@ impl MultisigCertificateContract {
             .expect("Issuer config not found");
 
         // Verify we have enough approvals
-        if request.approvals.len() < config.threshold as u32 {
+        if request.approvals.len() < config.threshold {
             panic!("Not enough approvals to issue certificate");
         }
 
@@ -530,7 +532,7 @@ impl MultisigCertificateContract {
     // Get all pending requests for an issuer
     pub fn get_pending_requests_for_issuer(
         env: Env,
-        issuer: Address,
+        _issuer: Address,
         pagination: Pagination,
     ) -> PaginatedResult {
         // In a real implementation, this would iterate through all pending requests
@@ -551,7 +553,7 @@ impl MultisigCertificateContract {
     // Get all pending requests for a signer
     pub fn get_pending_requests_for_signer(
         env: Env,
-        signer: Address,
+        _signer: Address,
         pagination: Pagination,
     ) -> PaginatedResult {
         // Similar to above, this would require a more complex storage structure
@@ -607,16 +609,12 @@ impl MultisigCertificateContract {
 }
 
 // Internal data keys for storage
-mod self {
-    use super::*;
-
-    #[contracttype]
-    #[derive(Clone)]
-    pub enum DataKey {
-        MultisigConfig(Address), // Issuer address -> MultisigConfig
-        IssuerAdmin(Address),    // Issuer address -> Admin address
-        PendingRequest(String),  // Request ID -> PendingRequest
-        RequestByIssuer(Address, String), // (Issuer, Request ID) -> exists
-        RequestBySigner(Address, String), // (Signer, Request ID) -> exists
-    }
-}
\ No newline at end of file
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    MultisigConfig(Address),          // Issuer address -> MultisigConfig
+    IssuerAdmin(Address),             // Issuer address -> Admin address
+    PendingRequest(String),           // Request ID -> PendingRequest
+    RequestByIssuer(Address, String), // (Issuer, Request ID) -> exists
+    RequestBySigner(Address, String), // (Signer, Request ID) -> exists
+}
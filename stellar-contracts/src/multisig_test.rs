@@ -1,6 +1,6 @@
 #![cfg(test)]
 use super::multisig::*;
-use soroban_sdk::{Env, testutils::Address as _, Address, String, Vec};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, String};
 
 #[test]
 fn test_init_multisig_config() {
@@ -79,12 +79,12 @@ fn test_approve_request_success() {
     // First approval
     let result = client.approve_request(&request_id, &signer1);
     assert!(result.success);
-    assert_eq!(result.final_status, Some(RequestStatus::Pending));
+    assert_eq!(result.final_status, RequestStatus::Pending);
 
     // Second approval - should reach threshold and become approved
     let result = client.approve_request(&request_id, &signer2);
     assert!(result.success);
-    assert_eq!(result.final_status, Some(RequestStatus::Approved));
+    assert_eq!(result.final_status, RequestStatus::Approved);
 
     // Check the request status
     let request = client.get_pending_request(&request_id);
@@ -119,17 +119,17 @@ fn test_reject_request() {
     // Reject by one signer
     let result = client.reject_request(&request_id, &signer1, &None);
     assert!(result.success);
-    assert_eq!(result.final_status, Some(RequestStatus::Pending));
+    assert_eq!(result.final_status, RequestStatus::Pending);
 
     // Approve by another signer
     let result = client.approve_request(&request_id, &signer2);
     assert!(result.success);
-    assert_eq!(result.final_status, Some(RequestStatus::Pending));
+    assert_eq!(result.final_status, RequestStatus::Pending);
 
     // Approve by third signer - should succeed despite rejection
     let result = client.approve_request(&request_id, &signer3);
     assert!(result.success);
-    assert_eq!(result.final_status, Some(RequestStatus::Approved));
+    assert_eq!(result.final_status, RequestStatus::Approved);
 
     // Check the request status
     let request = client.get_pending_request(&request_id);
@@ -151,11 +151,11 @@ fn test_reject_request_impossible_approval() {
     let signer2 = Address::generate(&env);
     let signer3 = Address::generate(&env);
     
-    // Set up config with 3 signers, threshold 3 (all must approve)
+    // Set up config with 3 signers, threshold 2
     let signers = vec![&env, signer1.clone(), signer2.clone(), signer3.clone()];
     
     env.mock_all_auths();
-    client.init_multisig_config(&issuer, &3, &signers, &5, &admin);
+    client.init_multisig_config(&issuer, &2, &signers, &5, &admin);
 
     let request_id = String::from_str(&env, "req-004");
     let metadata = String::from_str(&env, "certificate metadata");
@@ -165,12 +165,12 @@ fn test_reject_request_impossible_approval() {
     // Reject by one signer
     let result = client.reject_request(&request_id, &signer1, &None);
     assert!(result.success);
-    assert_eq!(result.final_status, Some(RequestStatus::Pending));
+    assert_eq!(result.final_status, RequestStatus::Pending);
 
-    // Reject by second signer - now impossible to reach threshold of 3
+    // Reject by second signer - now impossible to reach threshold of 2
     let result = client.reject_request(&request_id, &signer2, &None);
     assert!(result.success);
-    assert_eq!(result.final_status, Some(RequestStatus::Rejected));
+    assert_eq!(result.final_status, RequestStatus::Rejected);
 
     // Check the request status
     let request = client.get_pending_request(&request_id);
@@ -223,7 +223,6 @@ fn test_cancel_request() {
     let admin = Address::generate(&env);
     let issuer = Address::generate(&env);
     let recipient = Address::generate(&env);
-    let proposer = Address::generate(&env); // Same as issuer in this case
     let signer1 = Address::generate(&env);
     let signer2 = Address::generate(&env);
     
@@ -264,7 +263,7 @@ fn test_update_multisig_config() {
 
     // Update the config
     let new_signers = vec![&env, signer1.clone(), signer2.clone()];
-    client.update_multisig_config(&Some(2), &Some(&new_signers), &Some(10), &issuer);
+    client.update_multisig_config(&issuer, &Some(2), &Some(new_signers), &Some(10));
 
     let config = client.get_multisig_config(&issuer);
     assert_eq!(config.threshold, 2);
@@ -297,7 +296,10 @@ fn test_invalid_approve_by_non_signer() {
     // Try to approve with non-signer - should fail
     let result = client.approve_request(&request_id, &non_signer);
     assert!(!result.success);
-    assert_eq!(result.message, "Approver is not an authorized signer");
+    assert_eq!(
+        result.message,
+        String::from_str(&env, "Approver is not an authorized signer")
+    );
 }
 
 #[test]
@@ -310,8 +312,9 @@ fn test_double_approval() {
     let issuer = Address::generate(&env);
     let recipient = Address::generate(&env);
     let signer1 = Address::generate(&env);
+    let signer2 = Address::generate(&env);
     
-    let signers = vec![&env, signer1.clone()];
+    let signers = vec![&env, signer1.clone(), signer2.clone()];
     
     env.mock_all_auths();
     client.init_multisig_config(&issuer, &2, &signers, &5, &admin);
@@ -328,7 +331,10 @@ fn test_double_approval() {
     // Second approval by same signer - should fail
     let result = client.approve_request(&request_id, &signer1);
     assert!(!result.success);
-    assert_eq!(result.message, "Request already approved by this signer");
+    assert_eq!(
+        result.message,
+        String::from_str(&env, "Request already approved by this signer")
+    );
 }
 
 #[test]
@@ -355,7 +361,7 @@ fn test_expired_request() {
 
     // Manually advance time to expire the request (in a real test, we'd use ledger time)
     // This is a simplified test - in reality we'd check the expiration in the contract
-    let is_expired = client.is_expired(&request_id);
+    assert!(!client.is_expired(&request_id));
     // Note: This depends on the current ledger time vs expiration time
     // For this test, we're just checking the function exists
 }
\ No newline at end of file